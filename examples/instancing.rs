@@ -0,0 +1,132 @@
+use plate::{VertexDescription, plate_macros};
+
+#[repr(C)]
+#[derive(plate_macros::Vertex)]
+struct Vert {
+    #[vertex(loc = 0, format = "R32G32_SFLOAT")]
+    pos: glam::Vec2,
+}
+
+#[repr(C)]
+struct Instance {
+    offset: glam::Vec2,
+    scale: f32,
+    color: glam::Vec3,
+}
+
+impl VertexDescription for Instance {
+    fn binding_descriptions() -> Vec<plate::VertexBindingDescription> {
+        vec![
+            plate::VertexBindingDescription::new(1, std::mem::size_of::<Self>() as u32, plate::InputRate::INSTANCE),
+        ]
+    }
+
+    fn attribute_descriptions() -> Vec<plate::VertexAttributeDescription> {
+        vec![
+            plate::VertexAttributeDescription::new(1, 1, memoffset::offset_of!(Self, offset) as u32, plate::Format::R32G32_SFLOAT),
+            plate::VertexAttributeDescription::new(1, 2, memoffset::offset_of!(Self, scale) as u32, plate::Format::R32_SFLOAT),
+            plate::VertexAttributeDescription::new(1, 3, memoffset::offset_of!(Self, color) as u32, plate::Format::R32G32B32_SFLOAT),
+        ]
+    }
+}
+
+const GRID_SIZE: usize = 32;
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let event_loop = winit::event_loop::EventLoop::new();
+    let window = winit::window::WindowBuilder::new().build(&event_loop)?;
+
+    let device = plate::Device::new(&Default::default(), &Default::default(), Some(&window))?;
+    let mut e = examples::App::new(&device, &window)?;
+    let pipeline = plate::pipeline::Pipeline::new(
+        &device,
+        &e.render_pass,
+        vk_shader_macros::include_glsl!("shaders/instancing/shader.vert"),
+        vk_shader_macros::include_glsl!("shaders/instancing/shader.frag"),
+        &plate::PipelineParameters {
+            vertex_binding_descriptions: Vert::binding_descriptions().into_iter().chain(Instance::binding_descriptions()).collect(),
+            vertex_attribute_descriptions: Vert::attribute_descriptions().into_iter().chain(Instance::attribute_descriptions()).collect(),
+            ..Default::default()
+        },
+    )?;
+
+    let cmd_pool = plate::CommandPool::new(&device)?;
+
+    let vertices = vec![
+        Vert { pos: glam::vec2(-0.5, -0.5) },
+        Vert { pos: glam::vec2(0.5, -0.5) },
+        Vert { pos: glam::vec2(0.5, 0.5) },
+        Vert { pos: glam::vec2(-0.5, 0.5) },
+    ];
+    let indices = vec![0, 1, 2, 2, 3, 0];
+
+    // One quad per grid cell, each with its own position, size and color
+    let cell = 2.0 / GRID_SIZE as f32;
+    let instances = (0..GRID_SIZE * GRID_SIZE)
+        .map(|i| {
+            let (x, y) = ((i % GRID_SIZE) as f32, (i / GRID_SIZE) as f32);
+            Instance {
+                offset: glam::vec2(-1.0 + cell * (x + 0.5), -1.0 + cell * (y + 0.5)),
+                scale: cell * 0.8,
+                color: glam::vec3(x / GRID_SIZE as f32, y / GRID_SIZE as f32, 1.0),
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let vert_buffer = plate::VertexBuffer::new(&device, &vertices, &cmd_pool)?;
+    let instance_buffer = plate::VertexBuffer::new(&device, &instances, &cmd_pool)?;
+    let index_buffer = plate::IndexBuffer::new(&device, &indices, &cmd_pool)?;
+
+    let frames = plate::FrameManager::new(&device, &Default::default())?;
+
+    let mut resized = false;
+    event_loop.run(move |event, _, control_flow| {
+        *control_flow = winit::event_loop::ControlFlow::Poll;
+        match event {
+            winit::event::Event::WindowEvent { event, window_id } if window_id == window.id() => {
+                match event {
+                    winit::event::WindowEvent::CloseRequested => {
+                        *control_flow = winit::event_loop::ControlFlow::Exit
+                    }
+                    winit::event::WindowEvent::Resized(_) => resized = true,
+                    _ => (),
+                }
+            }
+
+            winit::event::Event::MainEventsCleared => window.request_redraw(),
+            winit::event::Event::RedrawRequested(window_id) if window_id == window.id() => {
+                if resized {
+                    resized = false;
+                    e.recreate(&window).unwrap();
+                }
+
+                let frame = match frames.begin_frame(&e.swapchain) {
+                    Err(plate::Error::FrameError(plate::FrameError::OutOfDate)) => {
+                        resized = true;
+                        return
+                    }
+                    frame => frame.unwrap(),
+                };
+
+                frame.cmd_buffer.record(plate::CommandBufferUsageFlags::empty(), || {
+                    e.render_pass.begin(frame.cmd_buffer, &e.framebuffers[frame.image_index as usize]);
+                    pipeline.bind(frame.cmd_buffer, e.swapchain.extent());
+                    vert_buffer.bind_at(frame.cmd_buffer, 0);
+                    instance_buffer.bind_at(frame.cmd_buffer, 1);
+                    index_buffer.bind(frame.cmd_buffer);
+                    frame.cmd_buffer.draw_indexed(indices.len() as u32, instances.len() as u32, 0, 0, 0).unwrap();
+                    e.render_pass.end(frame.cmd_buffer);
+                }).unwrap();
+
+                let status = frame.submit_and_present().unwrap();
+                if resized || status != plate::SwapchainStatus::Optimal {
+                    resized = false;
+                    e.recreate(&window).unwrap();
+                }
+            }
+
+            winit::event::Event::LoopDestroyed => device.wait_idle().unwrap(),
+            _ => (),
+        }
+    })
+}
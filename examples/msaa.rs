@@ -0,0 +1,68 @@
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let event_loop = winit::event_loop::EventLoop::new();
+    let window = winit::window::WindowBuilder::new().build(&event_loop)?;
+
+    let device = plate::Device::new(&Default::default(), &Default::default(), Some(&window))?;
+    let mut e = examples::App::with_samples(&device, &window, plate::SampleCount::Sample4)?;
+
+    let pipeline = plate::pipeline::Pipeline::new(
+        &device,
+        &e.render_pass,
+        vk_shader_macros::include_glsl!("shaders/triangle/shader.vert"),
+        vk_shader_macros::include_glsl!("shaders/triangle/shader.frag"),
+        &plate::PipelineParameters {
+            rasterization_samples: e.swapchain.samples(),
+            ..Default::default()
+        },
+    )?;
+
+    let frames = plate::FrameManager::new(&device, &Default::default())?;
+
+    let mut resized = false;
+    event_loop.run(move |event, _, control_flow| {
+        *control_flow = winit::event_loop::ControlFlow::Poll;
+        match event {
+            winit::event::Event::WindowEvent { event, window_id } if window_id == window.id() => {
+                match event {
+                    winit::event::WindowEvent::CloseRequested => {
+                        *control_flow = winit::event_loop::ControlFlow::Exit
+                    }
+                    winit::event::WindowEvent::Resized(_) => resized = true,
+                    _ => (),
+                }
+            }
+
+            winit::event::Event::MainEventsCleared => window.request_redraw(),
+            winit::event::Event::RedrawRequested(window_id) if window_id == window.id() => {
+                if resized {
+                    resized = false;
+                    e.recreate(&window).unwrap();
+                }
+
+                let frame = match frames.begin_frame(&e.swapchain) {
+                    Err(plate::Error::FrameError(plate::FrameError::OutOfDate)) => {
+                        resized = true;
+                        return
+                    }
+                    frame => frame.unwrap(),
+                };
+
+                frame.cmd_buffer.record(plate::CommandBufferUsageFlags::empty(), || {
+                    e.render_pass.begin(frame.cmd_buffer, &e.framebuffers[frame.image_index as usize]);
+                    pipeline.bind(frame.cmd_buffer, e.swapchain.extent());
+                    frame.cmd_buffer.draw(3, 1, 0, 0).unwrap();
+                    e.render_pass.end(frame.cmd_buffer);
+                }).unwrap();
+
+                let status = frame.submit_and_present().unwrap();
+                if resized || status != plate::SwapchainStatus::Optimal {
+                    resized = false;
+                    e.recreate(&window).unwrap();
+                }
+            }
+
+            winit::event::Event::LoopDestroyed => device.wait_idle().unwrap(),
+            _ => (),
+        }
+    })
+}
@@ -0,0 +1,77 @@
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let device = plate::Device::new(&Default::default(), &Default::default(), None)?;
+    let offscreen = plate::OffscreenChain::new(&device, 800, 600, &Default::default())?;
+
+    let color_attachment = plate::Attachment {
+        format: offscreen.format,
+        load_op: plate::AttachmentLoadOp::CLEAR,
+        store_op: plate::AttachmentStoreOp::STORE,
+        initial_layout: plate::ImageLayout::UNDEFINED,
+        final_layout: plate::ImageLayout::TRANSFER_SRC_OPTIMAL,
+        samples: plate::SampleCount::Sample1,
+    };
+
+    let subpass = plate::SubpassDescription {
+        color_attachments: &[plate::AttachmentReference { attachment: 0, layout: plate::ImageLayout::COLOR_ATTACHMENT_OPTIMAL }],
+        ..Default::default()
+    };
+
+    let dependency = plate::SubpassDependency {
+        src_subpass: plate::Subpass::EXTERNAL,
+        dst_subpass: plate::Subpass(0),
+        src_stage_mask: plate::PipelineStage::COLOR_ATTACHMENT_OUTPUT,
+        dst_stage_mask: plate::PipelineStage::COLOR_ATTACHMENT_OUTPUT,
+        src_access_mask: plate::AccessFlags::NONE,
+        dst_access_mask: plate::AccessFlags::COLOR_ATTACHMENT_WRITE,
+    };
+
+    let render_pass = plate::RenderPass::new(&device, &[color_attachment], &[subpass], &[dependency])?;
+
+    let (width, height) = offscreen.extent();
+    let framebuffers = offscreen.images
+        .iter()
+        .map(|image| plate::Framebuffer::new(&device, &render_pass, &[image], width, height))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let pipeline = plate::pipeline::Pipeline::new(
+        &device,
+        &render_pass,
+        vk_shader_macros::include_glsl!("shaders/triangle/shader.vert"),
+        vk_shader_macros::include_glsl!("shaders/triangle/shader.frag"),
+        &Default::default(),
+    )?;
+
+    let cmd_pool = plate::CommandPool::new(&device)?;
+    let cmd_buffer = cmd_pool.alloc_cmd_buffer(plate::CommandBufferLevel::PRIMARY)?;
+
+    let fence = plate::Fence::new(&device, plate::FenceFlags::SIGNALED)?;
+    let acquire_sem = plate::Semaphore::new(&device, plate::SemaphoreFlags::empty())?;
+    let present_sem = plate::Semaphore::new(&device, plate::SemaphoreFlags::empty())?;
+
+    fence.wait_and_reset(None)?;
+
+    let (i, _) = offscreen.next_image(&acquire_sem)?;
+
+    cmd_buffer.record(plate::CommandBufferUsageFlags::empty(), || {
+        render_pass.begin(&cmd_buffer, &framebuffers[i as usize]);
+        pipeline.bind(&cmd_buffer, offscreen.extent());
+        cmd_buffer.draw(3, 1, 0, 0).unwrap();
+        render_pass.end(&cmd_buffer);
+    })?;
+
+    device.queue_submit(
+        &cmd_buffer,
+        plate::PipelineStage::COLOR_ATTACHMENT_OUTPUT,
+        Some(&acquire_sem),
+        Some(&present_sem),
+        Some(&fence),
+    )?;
+
+    offscreen.present(i, &present_sem)?;
+
+    let pixels = offscreen.download(i, &cmd_pool)?;
+    image::save_buffer("offscreen.png", &pixels, width, height, image::ColorType::Rgba8)?;
+
+    device.wait_idle()?;
+    Ok(())
+}
@@ -0,0 +1,116 @@
+use plate::{VertexDescription, plate_macros};
+
+#[repr(C)]
+#[derive(plate_macros::Vertex)]
+struct Vert {
+    #[vertex(loc = 0, format = "R32G32_SFLOAT")]
+    pos: glam::Vec2,
+    #[vertex(loc = 1, format = "R32G32B32_SFLOAT")]
+    color: glam::Vec3,
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let event_loop = winit::event_loop::EventLoop::new();
+    let window = winit::window::WindowBuilder::new().build(&event_loop)?;
+
+    let device = plate::Device::new(&Default::default(), &Default::default(), Some(&window))?;
+    let mut e = examples::App::new(&device, &window)?;
+
+    let pipeline = plate::pipeline::Pipeline::new(
+        &device,
+        &e.render_pass,
+        vk_shader_macros::include_glsl!("shaders/push_constants/shader.vert"),
+        vk_shader_macros::include_glsl!("shaders/push_constants/shader.frag"),
+        &plate::PipelineParameters {
+            vertex_binding_descriptions: Vert::binding_descriptions(),
+            vertex_attribute_descriptions: Vert::attribute_descriptions(),
+            push_constant_ranges: &[plate::PushConstantRange {
+                stage: plate::ShaderStage::VERTEX,
+                offset: 0,
+                size: std::mem::size_of::<glam::Mat4>() as u32,
+            }],
+            ..Default::default()
+        },
+    )?;
+
+    let cmd_pool = plate::CommandPool::new(&device)?;
+
+    let vertices = vec![
+        Vert { pos: glam::vec2(-0.5, -0.5), color: glam::vec3(1.0, 0.0, 0.0) },
+        Vert { pos: glam::vec2(0.5, -0.5), color: glam::vec3(0.0, 1.0, 0.0) },
+        Vert { pos: glam::vec2(0.5, 0.5), color: glam::vec3(0.0, 0.0, 1.0) },
+        Vert { pos: glam::vec2(-0.5, 0.5), color: glam::vec3(1.0, 1.0, 1.0) },
+    ];
+    let indices = vec![0, 1, 2, 2, 3, 0];
+
+    let vert_buffer = plate::VertexBuffer::new(&device, &vertices, &cmd_pool)?;
+    let index_buffer = plate::IndexBuffer::new(&device, &indices, &cmd_pool)?;
+
+    let frames = plate::FrameManager::new(&device, &Default::default())?;
+
+    let mut rot = 0.0;
+    let mut resized = false;
+    event_loop.run(move |event, _, control_flow| {
+        *control_flow = winit::event_loop::ControlFlow::Poll;
+        match event {
+            winit::event::Event::WindowEvent { event, window_id } if window_id == window.id() => {
+                match event {
+                    winit::event::WindowEvent::CloseRequested => {
+                        *control_flow = winit::event_loop::ControlFlow::Exit
+                    }
+                    winit::event::WindowEvent::Resized(_) => resized = true,
+                    _ => (),
+                }
+            }
+
+            winit::event::Event::MainEventsCleared => window.request_redraw(),
+            winit::event::Event::RedrawRequested(window_id) if window_id == window.id() => {
+                rot += 0.01;
+
+                if resized {
+                    resized = false;
+                    e.recreate(&window).unwrap();
+                }
+
+                let frame = match frames.begin_frame(&e.swapchain) {
+                    Err(plate::Error::FrameError(plate::FrameError::OutOfDate)) => {
+                        resized = true;
+                        return
+                    }
+                    frame => frame.unwrap(),
+                };
+
+                frame.cmd_buffer.record(plate::CommandBufferUsageFlags::empty(), || {
+                    e.render_pass.begin(frame.cmd_buffer, &e.framebuffers[frame.image_index as usize]);
+
+                    pipeline.bind(frame.cmd_buffer, e.swapchain.extent());
+                    vert_buffer.bind(frame.cmd_buffer);
+                    index_buffer.bind(frame.cmd_buffer);
+
+                    let left = glam::Mat4::from_translation(glam::vec3(-0.5, 0.0, 0.0))
+                        * glam::Mat4::from_scale(glam::vec3(0.5, 0.5, 1.0))
+                        * glam::Mat4::from_rotation_z(rot);
+                    pipeline.push_constants(frame.cmd_buffer, plate::ShaderStage::VERTEX, 0, &left).unwrap();
+                    frame.cmd_buffer.draw_indexed(indices.len() as u32, 1, 0, 0, 0).unwrap();
+
+                    let right = glam::Mat4::from_translation(glam::vec3(0.5, 0.0, 0.0))
+                        * glam::Mat4::from_scale(glam::vec3(0.5, 0.5, 1.0))
+                        * glam::Mat4::from_rotation_z(-rot);
+                    pipeline.push_constants(frame.cmd_buffer, plate::ShaderStage::VERTEX, 0, &right).unwrap();
+                    frame.cmd_buffer.draw_indexed(indices.len() as u32, 1, 0, 0, 0).unwrap();
+
+                    e.render_pass.end(frame.cmd_buffer);
+                }).unwrap();
+
+                let status = frame.submit_and_present().unwrap();
+                if resized || status != plate::SwapchainStatus::Optimal {
+                    resized = false;
+                    e.recreate(&window).unwrap();
+                }
+            }
+
+            winit::event::Event::LoopDestroyed => device.wait_idle().unwrap(),
+            _ => (),
+        }
+    })
+}
@@ -5,29 +5,38 @@ pub struct App {
     pub swapchain: plate::Swapchain,
     pub render_pass: plate::RenderPass,
     pub framebuffers: Vec<plate::Framebuffer>,
-    depth_image: plate::Image,
 }
 
 impl App {
     pub fn new(device: &Arc<plate::Device>, window: &winit::window::Window) -> Result<Self, plate::Error> {
-        let swapchain = plate::swapchain::Swapchain::new(&device, &window)?;
+        Self::with_samples(device, window, plate::SampleCount::Sample1)
+    }
 
-        let depth_image = plate::Image::new(
-            device,
-            swapchain.extent().0,
-            swapchain.extent().1,
-            swapchain.depth_format,
-            plate::ImageLayout::UNDEFINED,
-            plate::ImageUsageFlags::DEPTH_STENCIL_ATTACHMENT,
-            plate::ImageAspectFlags::DEPTH,
+    pub fn with_samples(device: &Arc<plate::Device>, window: &winit::window::Window, samples: plate::SampleCount) -> Result<Self, plate::Error> {
+        let swapchain = plate::swapchain::Swapchain::new(
+            &device,
+            &window,
+            &plate::SwapchainParameters {
+                depth_image: true,
+                samples,
+                ..Default::default()
+            },
         )?;
+        let multisampled = swapchain.msaa_image.is_some();
 
         let color_attachment = plate::Attachment {
             format: swapchain.surface_format,
             load_op: plate::AttachmentLoadOp::CLEAR,
-            store_op: plate::AttachmentStoreOp::STORE,
+            store_op: match multisampled {
+                true => plate::AttachmentStoreOp::DONT_CARE,
+                false => plate::AttachmentStoreOp::STORE,
+            },
             initial_layout: plate::ImageLayout::UNDEFINED,
-            final_layout: plate::ImageLayout::PRESENT_SRC_KHR,
+            final_layout: match multisampled {
+                true => plate::ImageLayout::COLOR_ATTACHMENT_OPTIMAL,
+                false => plate::ImageLayout::PRESENT_SRC_KHR,
+            },
+            samples: swapchain.samples(),
         };
         let depth_attachment = plate::Attachment {
             format: swapchain.depth_format,
@@ -35,11 +44,25 @@ impl App {
             store_op: plate::AttachmentStoreOp::STORE,
             initial_layout: plate::ImageLayout::UNDEFINED,
             final_layout: plate::ImageLayout::DEPTH_STENCIL_ATTACHMENT_OPTIMAL,
+            samples: swapchain.samples(),
+        };
+        let resolve_attachment = plate::Attachment {
+            format: swapchain.surface_format,
+            load_op: plate::AttachmentLoadOp::DONT_CARE,
+            store_op: plate::AttachmentStoreOp::STORE,
+            initial_layout: plate::ImageLayout::UNDEFINED,
+            final_layout: plate::ImageLayout::PRESENT_SRC_KHR,
+            samples: plate::SampleCount::Sample1,
         };
 
+        let resolve_attachments = [plate::AttachmentReference { attachment: 2, layout: plate::ImageLayout::COLOR_ATTACHMENT_OPTIMAL }];
         let subpass = plate::SubpassDescription {
             color_attachments: &[plate::AttachmentReference { attachment: 0, layout: plate::ImageLayout::COLOR_ATTACHMENT_OPTIMAL }],
             depth_attachment: Some(plate::AttachmentReference { attachment: 1, layout: plate::ImageLayout::DEPTH_STENCIL_ATTACHMENT_OPTIMAL }),
+            resolve_attachments: match multisampled {
+                true => &resolve_attachments,
+                false => &[],
+            },
             ..Default::default()
         };
 
@@ -52,42 +75,39 @@ impl App {
             dst_access_mask: plate::AccessFlags::COLOR_ATTACHMENT_WRITE | plate::AccessFlags::DEPTH_STENCIL_ATTACHMENT_WRITE,
         };
 
-        let render_pass = plate::RenderPass::new(&device, &[color_attachment, depth_attachment], &[subpass], &[dependency])?;
+        let render_pass = match multisampled {
+            true => plate::RenderPass::new(&device, &[color_attachment, depth_attachment, resolve_attachment], &[subpass], &[dependency])?,
+            false => plate::RenderPass::new(&device, &[color_attachment, depth_attachment], &[subpass], &[dependency])?,
+        };
 
-        let framebuffers = swapchain.images
-            .iter()
-            .map(|image| {
-                plate::Framebuffer::new(&device, &render_pass, &[image, &depth_image], swapchain.extent().0, swapchain.extent().1)
-            })
-            .collect::<Result<Vec<_>, _>>()?;
+        let framebuffers = Self::framebuffers(device, &swapchain, &render_pass)?;
 
         Ok(Self {
             device: Arc::clone(&device),
             swapchain,
             render_pass,
             framebuffers,
-            depth_image,
         })
     }
 
-    pub fn recreate(&mut self) -> Result<(), plate::Error> {
-        self.depth_image = plate::Image::new(
-            &self.device,
-            self.swapchain.extent().0,
-            self.swapchain.extent().1,
-            self.swapchain.depth_format,
-            plate::ImageLayout::UNDEFINED,
-            plate::ImageUsageFlags::DEPTH_STENCIL_ATTACHMENT,
-            plate::ImageAspectFlags::DEPTH,
-        )?;
+    pub fn recreate(&mut self, window: &winit::window::Window) -> Result<(), plate::Error> {
+        self.swapchain.recreate(window)?;
+        self.framebuffers = Self::framebuffers(&self.device, &self.swapchain, &self.render_pass)?;
 
-        self.framebuffers = self.swapchain.images
+        Ok(())
+    }
+
+    fn framebuffers(device: &Arc<plate::Device>, swapchain: &plate::Swapchain, render_pass: &plate::RenderPass) -> Result<Vec<plate::Framebuffer>, plate::Error> {
+        let depth_image = swapchain.depth_image.as_ref().expect("The swapchain was created with a depth image");
+
+        swapchain.images
             .iter()
             .map(|image| {
-                plate::Framebuffer::new(&self.device, &self.render_pass, &[image, &self.depth_image], self.swapchain.extent().0, self.swapchain.extent().1)
+                match &swapchain.msaa_image {
+                    Some(msaa_image) => plate::Framebuffer::new(device, render_pass, &[msaa_image, depth_image, image], swapchain.extent().0, swapchain.extent().1),
+                    None => plate::Framebuffer::new(device, render_pass, &[image, depth_image], swapchain.extent().0, swapchain.extent().1),
+                }
             })
-            .collect::<Result<Vec<_>, _>>()?;
-
-        Ok(())
+            .collect()
     }
 }
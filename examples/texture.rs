@@ -26,15 +26,17 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         &[
             plate::LayoutBinding {
                 binding: 0,
-                ty: plate::DescriptorType::UNIFORM_BUFFER,
+                ty: plate::DescriptorType::UNIFORM_BUFFER_DYNAMIC,
                 stage: plate::ShaderStage::VERTEX,
                 count: 1,
+                ..Default::default()
             },
             plate::LayoutBinding {
                 binding: 1,
                 ty: plate::DescriptorType::COMBINED_IMAGE_SAMPLER,
                 stage: plate::ShaderStage::FRAGMENT,
                 count: 1,
+                ..Default::default()
             },
         ],
     )?;
@@ -51,8 +53,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         },
     )?;
 
-    let cmd_pool = plate::CommandPool::new(&device)?;
-    let cmd_buffer = cmd_pool.alloc_cmd_buffer(plate::CommandBufferLevel::PRIMARY)?;
+    let mut upload = plate::UploadContext::new(&device)?;
 
     let vertices = vec![
         Vert { pos: glam::vec2(-0.5, -0.5), uv: glam::vec2(1.0, 0.0) },
@@ -62,14 +63,14 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     ];
     let indices = vec![0, 1, 2, 2, 3, 0];
 
-    let vert_buffer = plate::VertexBuffer::new(&device, &vertices, &cmd_pool)?;
-    let index_buffer = plate::IndexBuffer::new(&device, &indices, &cmd_pool)?;
+    let vert_buffer = upload.vertex_buffer(&vertices)?;
+    let index_buffer = upload.index_buffer(&indices)?;
 
     let descriptor_pool = plate::DescriptorPool::new(
         &device,
         &[
             plate::PoolSize {
-                ty: plate::DescriptorType::UNIFORM_BUFFER,
+                ty: plate::DescriptorType::UNIFORM_BUFFER_DYNAMIC,
                 count: 1,
             },
             plate::PoolSize {
@@ -80,30 +81,33 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         2
     )?;
 
-    let ubo: plate::Buffer<Ubo> = plate::Buffer::new(
+    let frames = plate::FrameManager::new(&device, &Default::default())?;
+
+    let mut ubo: plate::PerFrameBuffer<Ubo> = plate::PerFrameBuffer::new(
         &device,
         1,
+        frames.frames_in_flight(),
         plate::BufferUsageFlags::UNIFORM_BUFFER,
-        plate::SharingMode::EXCLUSIVE,
-        plate::MemoryPropertyFlags::HOST_VISIBLE | plate::MemoryPropertyFlags::HOST_COHERENT,
     )?;
 
     let tex = image::open("examples/texture.jpg")?.flipv();
-    let image = plate::Texture::new(&device, &cmd_pool, tex.width(), tex.height(), &tex.to_rgba8().into_raw())?;
+    let image = upload.texture(tex.width(), tex.height(), &tex.to_rgba8().into_raw(), &plate::TextureParameters {
+        mipmaps: true,
+        ..Default::default()
+    })?;
+    // Uploads run on the transfer queue while the rest of the setup continues
+    let pending = upload.submit()?;
     let sampler = plate::Sampler::new(&device, &Default::default())?;
 
     let descriptor_set = plate::DescriptorAllocator::new(&device)
-        .add_buffer_binding(0, plate::DescriptorType::UNIFORM_BUFFER, &ubo)
+        .add_dynamic_buffer_binding(0, 1, plate::DescriptorType::UNIFORM_BUFFER_DYNAMIC, ubo.buffer())
         .add_image_binding(1, plate::DescriptorType::COMBINED_IMAGE_SAMPLER, &image, &sampler, plate::ImageLayout::SHADER_READ_ONLY_OPTIMAL)
         .allocate(&set_layout, &descriptor_pool)?;
 
-    let mut ubo = ubo.map()?;
-
-    let fence = plate::Fence::new(&device, plate::FenceFlags::SIGNALED)?;
-    let acquire_sem = plate::Semaphore::new(&device, plate::SemaphoreFlags::empty())?;
-    let present_sem = plate::Semaphore::new(&device, plate::SemaphoreFlags::empty())?;
+    pending.wait()?;
 
     let mut rot = 0.0;
+    let mut resized = false;
     event_loop.run(move |event, _, control_flow| {
         *control_flow = winit::event_loop::ControlFlow::Poll;
         match event {
@@ -112,7 +116,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                     winit::event::WindowEvent::CloseRequested => {
                         *control_flow = winit::event_loop::ControlFlow::Exit
                     }
-                    winit::event::WindowEvent::Resized(_) => e.recreate().unwrap(),
+                    winit::event::WindowEvent::Resized(_) => resized = true,
                     _ => (),
                 }
             }
@@ -121,34 +125,38 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             winit::event::Event::RedrawRequested(window_id) if window_id == window.id() => {
                 rot += 0.01;
 
-                fence.wait().unwrap();
-                fence.reset().unwrap();
+                if resized {
+                    resized = false;
+                    e.recreate(&window).unwrap();
+                }
 
-                let (i, _) = e.swapchain.next_image(&acquire_sem).unwrap();
+                let frame = match frames.begin_frame(&e.swapchain) {
+                    Err(plate::Error::FrameError(plate::FrameError::OutOfDate)) => {
+                        resized = true;
+                        return
+                    }
+                    frame => frame.unwrap(),
+                };
 
-                ubo.write(&[Ubo { model: glam::Mat4::from_rotation_z(rot) }]);
+                ubo.write(frame.index, &[Ubo { model: glam::Mat4::from_rotation_z(rot) }]);
 
-                cmd_buffer.record(plate::CommandBufferUsageFlags::empty(), || {
-                    e.render_pass.begin(&cmd_buffer, &e.framebuffers[i as usize]);
+                frame.cmd_buffer.record(plate::CommandBufferUsageFlags::empty(), || {
+                    e.render_pass.begin(frame.cmd_buffer, &e.framebuffers[frame.image_index as usize]);
 
-                    pipeline.bind(&cmd_buffer, e.swapchain.extent());
-                    vert_buffer.bind(&cmd_buffer);
-                    index_buffer.bind(&cmd_buffer);
-                    descriptor_set.bind(&cmd_buffer, &pipeline, 0, &[]).unwrap();
+                    pipeline.bind(frame.cmd_buffer, e.swapchain.extent());
+                    vert_buffer.bind(frame.cmd_buffer);
+                    index_buffer.bind(frame.cmd_buffer);
+                    descriptor_set.bind(frame.cmd_buffer, &pipeline, 0, &[ubo.dynamic_offset(frame.index)]).unwrap();
 
-                    cmd_buffer.draw_indexed(indices.len() as u32, 1, 0, 0, 0);
-                    e.render_pass.end(&cmd_buffer);
+                    frame.cmd_buffer.draw_indexed(indices.len() as u32, 1, 0, 0, 0).unwrap();
+                    e.render_pass.end(frame.cmd_buffer);
                 }).unwrap();
 
-                device.queue_submit(
-                    &cmd_buffer,
-                    plate::PipelineStage::COLOR_ATTACHMENT_OUTPUT,
-                    Some(&acquire_sem),
-                    Some(&present_sem),
-                    Some(&fence),
-                ).unwrap();
-
-                e.swapchain.present(i, &present_sem).unwrap();
+                let status = frame.submit_and_present().unwrap();
+                if resized || status != plate::SwapchainStatus::Optimal {
+                    resized = false;
+                    e.recreate(&window).unwrap();
+                }
             }
 
             winit::event::Event::LoopDestroyed => device.wait_idle().unwrap(),
@@ -0,0 +1,87 @@
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let event_loop = winit::event_loop::EventLoop::new();
+    let window = winit::window::WindowBuilder::new().build(&event_loop)?;
+
+    let device = plate::Device::new(&Default::default(), &Default::default(), Some(&window))?;
+    let mut e = examples::App::new(&device, &window)?;
+
+    let pipeline = plate::pipeline::Pipeline::new(
+        &device,
+        &e.render_pass,
+        vk_shader_macros::include_glsl!("shaders/triangle/shader.vert"),
+        vk_shader_macros::include_glsl!("shaders/triangle/shader.frag"),
+        &Default::default(),
+    )?;
+
+    let frames = plate::FrameManager::new(&device, &Default::default())?;
+
+    // One pool per frame in flight, so the timestamps of a frame are only read after its fence
+    // is waited on by begin_frame()
+    let query_pools = (0..frames.frames_in_flight())
+        .map(|_| plate::QueryPool::new(&device, 2))
+        .collect::<Result<Vec<_>, _>>()?;
+    let mut written = vec![false; frames.frames_in_flight()];
+    let mut frame_count = 0;
+
+    let mut resized = false;
+    event_loop.run(move |event, _, control_flow| {
+        *control_flow = winit::event_loop::ControlFlow::Poll;
+        match event {
+            winit::event::Event::WindowEvent { event, window_id } if window_id == window.id() => {
+                match event {
+                    winit::event::WindowEvent::CloseRequested => {
+                        *control_flow = winit::event_loop::ControlFlow::Exit
+                    }
+                    winit::event::WindowEvent::Resized(_) => resized = true,
+                    _ => (),
+                }
+            }
+
+            winit::event::Event::MainEventsCleared => window.request_redraw(),
+            winit::event::Event::RedrawRequested(window_id) if window_id == window.id() => {
+                if resized {
+                    resized = false;
+                    e.recreate(&window).unwrap();
+                }
+
+                let frame = match frames.begin_frame(&e.swapchain) {
+                    Err(plate::Error::FrameError(plate::FrameError::OutOfDate)) => {
+                        resized = true;
+                        return
+                    }
+                    frame => frame.unwrap(),
+                };
+
+                let query_pool = &query_pools[frame.index];
+                if written[frame.index] {
+                    let timestamps = query_pool.results().unwrap();
+                    frame_count += 1;
+                    if frame_count == 1000 {
+                        frame_count = 0;
+                        println!("Draw took {} ns", timestamps[1] - timestamps[0]);
+                    }
+                }
+
+                frame.cmd_buffer.record(plate::CommandBufferUsageFlags::empty(), || {
+                    frame.cmd_buffer.reset_query_pool(query_pool);
+                    e.render_pass.begin(frame.cmd_buffer, &e.framebuffers[frame.image_index as usize]);
+                    pipeline.bind(frame.cmd_buffer, e.swapchain.extent());
+                    frame.cmd_buffer.write_timestamp(query_pool, plate::PipelineStage::TOP_OF_PIPE, 0);
+                    frame.cmd_buffer.draw(3, 1, 0, 0).unwrap();
+                    frame.cmd_buffer.write_timestamp(query_pool, plate::PipelineStage::BOTTOM_OF_PIPE, 1);
+                    e.render_pass.end(frame.cmd_buffer);
+                }).unwrap();
+                written[frame.index] = true;
+
+                let status = frame.submit_and_present().unwrap();
+                if resized || status != plate::SwapchainStatus::Optimal {
+                    resized = false;
+                    e.recreate(&window).unwrap();
+                }
+            }
+
+            winit::event::Event::LoopDestroyed => device.wait_idle().unwrap(),
+            _ => (),
+        }
+    })
+}
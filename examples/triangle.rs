@@ -13,13 +13,9 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         &Default::default(),
     )?;
 
-    let cmd_pool = plate::CommandPool::new(&device)?;
-    let cmd_buffer = cmd_pool.alloc_cmd_buffer(plate::CommandBufferLevel::PRIMARY)?;
-
-    let fence = plate::Fence::new(&device, plate::FenceFlags::SIGNALED)?;
-    let acquire_sem = plate::Semaphore::new(&device, plate::SemaphoreFlags::empty())?;
-    let present_sem = plate::Semaphore::new(&device, plate::SemaphoreFlags::empty())?;
+    let frames = plate::FrameManager::new(&device, &Default::default())?;
 
+    let mut resized = false;
     event_loop.run(move |event, _, control_flow| {
         *control_flow = winit::event_loop::ControlFlow::Poll;
         match event {
@@ -28,34 +24,38 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                     winit::event::WindowEvent::CloseRequested => {
                         *control_flow = winit::event_loop::ControlFlow::Exit
                     }
-                    winit::event::WindowEvent::Resized(_) => e.recreate().unwrap(),
+                    winit::event::WindowEvent::Resized(_) => resized = true,
                     _ => (),
                 }
             }
 
             winit::event::Event::MainEventsCleared => window.request_redraw(),
             winit::event::Event::RedrawRequested(window_id) if window_id == window.id() => {
-                fence.wait().unwrap();
-                fence.reset().unwrap();
+                if resized {
+                    resized = false;
+                    e.recreate(&window).unwrap();
+                }
 
-                let (i, _) = e.swapchain.next_image(&acquire_sem).unwrap();
+                let frame = match frames.begin_frame(&e.swapchain) {
+                    Err(plate::Error::FrameError(plate::FrameError::OutOfDate)) => {
+                        resized = true;
+                        return
+                    }
+                    frame => frame.unwrap(),
+                };
 
-                cmd_buffer.record(plate::CommandBufferUsageFlags::empty(), || {
-                    e.render_pass.begin(&cmd_buffer, &e.framebuffers[i as usize]);
-                    pipeline.bind(&cmd_buffer, e.swapchain.extent());
-                    cmd_buffer.draw(3, 1, 0, 0);
-                    e.render_pass.end(&cmd_buffer);
+                frame.cmd_buffer.record(plate::CommandBufferUsageFlags::empty(), || {
+                    e.render_pass.begin(frame.cmd_buffer, &e.framebuffers[frame.image_index as usize]);
+                    pipeline.bind(frame.cmd_buffer, e.swapchain.extent());
+                    frame.cmd_buffer.draw(3, 1, 0, 0).unwrap();
+                    e.render_pass.end(frame.cmd_buffer);
                 }).unwrap();
 
-                device.queue_submit(
-                    &cmd_buffer,
-                    plate::PipelineStage::COLOR_ATTACHMENT_OUTPUT,
-                    Some(&acquire_sem),
-                    Some(&present_sem),
-                    Some(&fence),
-                ).unwrap();
-
-                e.swapchain.present(i, &present_sem).unwrap();
+                let status = frame.submit_and_present().unwrap();
+                if resized || status != plate::SwapchainStatus::Optimal {
+                    resized = false;
+                    e.recreate(&window).unwrap();
+                }
             }
 
             winit::event::Event::LoopDestroyed => device.wait_idle().unwrap(),
@@ -5,8 +5,8 @@ use plate::{VertexDescription, plate_macros};
 struct Vert {
     #[vertex(loc = 0, format = "R32G32_SFLOAT")]
     pos: glam::Vec2,
-    #[vertex(loc = 1, format = "R32G32B32_SFLOAT")]
-    color: glam::Vec3,
+    #[vertex(loc = 1, format = "R8G8B8A8_UNORM")]
+    color: [u8; 4],
 }
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -28,19 +28,17 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     )?;
 
     let cmd_pool = plate::CommandPool::new(&device)?;
-    let cmd_buffer = cmd_pool.alloc_cmd_buffer(plate::CommandBufferLevel::PRIMARY)?;
 
     let vertices = vec![
-        Vert { pos: glam::vec2(0.0, -0.5), color: glam::vec3(1.0, 0.0, 0.0) },
-        Vert { pos: glam::vec2(0.5, 0.5), color: glam::vec3(0.0, 1.0, 0.0) },
-        Vert { pos: glam::vec2(-0.5, 0.5), color: glam::vec3(0.0, 0.0, 1.0) },
+        Vert { pos: glam::vec2(0.0, -0.5), color: [255, 0, 0, 255] },
+        Vert { pos: glam::vec2(0.5, 0.5), color: [0, 255, 0, 255] },
+        Vert { pos: glam::vec2(-0.5, 0.5), color: [0, 0, 255, 255] },
     ];
     let vert_buffer = plate::VertexBuffer::new(&device, &vertices, &cmd_pool)?;
 
-    let fence = plate::Fence::new(&device, plate::FenceFlags::SIGNALED)?;
-    let acquire_sem = plate::Semaphore::new(&device, plate::SemaphoreFlags::empty())?;
-    let present_sem = plate::Semaphore::new(&device, plate::SemaphoreFlags::empty())?;
+    let frames = plate::FrameManager::new(&device, &Default::default())?;
 
+    let mut resized = false;
     event_loop.run(move |event, _, control_flow| {
         *control_flow = winit::event_loop::ControlFlow::Poll;
         match event {
@@ -49,35 +47,39 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                     winit::event::WindowEvent::CloseRequested => {
                         *control_flow = winit::event_loop::ControlFlow::Exit
                     }
-                    winit::event::WindowEvent::Resized(_) => e.recreate().unwrap(),
+                    winit::event::WindowEvent::Resized(_) => resized = true,
                     _ => (),
                 }
             }
 
             winit::event::Event::MainEventsCleared => window.request_redraw(),
             winit::event::Event::RedrawRequested(window_id) if window_id == window.id() => {
-                fence.wait().unwrap();
-                fence.reset().unwrap();
+                if resized {
+                    resized = false;
+                    e.recreate(&window).unwrap();
+                }
 
-                let (i, _) = e.swapchain.next_image(&acquire_sem).unwrap();
+                let frame = match frames.begin_frame(&e.swapchain) {
+                    Err(plate::Error::FrameError(plate::FrameError::OutOfDate)) => {
+                        resized = true;
+                        return
+                    }
+                    frame => frame.unwrap(),
+                };
 
-                cmd_buffer.record(plate::CommandBufferUsageFlags::empty(), || {
-                    e.render_pass.begin(&cmd_buffer, &e.framebuffers[i as usize]);
-                    pipeline.bind(&cmd_buffer, e.swapchain.extent());
-                    vert_buffer.bind(&cmd_buffer);
-                    cmd_buffer.draw(vertices.len() as u32, 1, 0, 0);
-                    e.render_pass.end(&cmd_buffer);
+                frame.cmd_buffer.record(plate::CommandBufferUsageFlags::empty(), || {
+                    e.render_pass.begin(frame.cmd_buffer, &e.framebuffers[frame.image_index as usize]);
+                    pipeline.bind(frame.cmd_buffer, e.swapchain.extent());
+                    vert_buffer.bind(frame.cmd_buffer);
+                    frame.cmd_buffer.draw(vertices.len() as u32, 1, 0, 0).unwrap();
+                    e.render_pass.end(frame.cmd_buffer);
                 }).unwrap();
 
-                device.queue_submit(
-                    &cmd_buffer,
-                    plate::PipelineStage::COLOR_ATTACHMENT_OUTPUT,
-                    Some(&acquire_sem),
-                    Some(&present_sem),
-                    Some(&fence),
-                ).unwrap();
-
-                e.swapchain.present(i, &present_sem).unwrap();
+                let status = frame.submit_and_present().unwrap();
+                if resized || status != plate::SwapchainStatus::Optimal {
+                    resized = false;
+                    e.recreate(&window).unwrap();
+                }
             }
 
             winit::event::Event::LoopDestroyed => device.wait_idle().unwrap(),
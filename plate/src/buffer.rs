@@ -2,13 +2,38 @@ use std::{ffi, marker, mem, sync::Arc};
 
 use ash::vk;
 
-use crate::{Device, PipelineStage, command::*, Error, MemoryPropertyFlags};
+use crate::{DebugObject, Device, PipelineStage, command::*, Error, MemoryPropertyFlags, ObjectType, UploadContext};
 
 pub use vk::BufferUsageFlags as BufferUsageFlags;
 pub use vk::SharingMode as SharingMode;
+pub use vk::DrawIndirectCommand;
+pub use vk::DrawIndexedIndirectCommand;
+
+/// Errors from the buffer module.
+#[derive(thiserror::Error, Debug)]
+pub enum BufferError {
+    /// The requested memory properties are not a valid combination, such as
+    /// [`HOST_COHERENT`](MemoryPropertyFlags::HOST_COHERENT) without
+    /// [`HOST_VISIBLE`](MemoryPropertyFlags::HOST_VISIBLE).
+    #[error("Invalid memory properties {0:?}")]
+    InvalidMemoryProperties(MemoryPropertyFlags),
+    /// None of the device memory types support the requested properties for the buffer.
+    #[error("No memory type supports {0:?}")]
+    NoSuitableMemoryType(MemoryPropertyFlags),
+    /// The device or the host ran out of memory while allocating the buffer.
+    #[error("Out of memory allocating {size} bytes of {memory_properties:?} memory")]
+    OutOfMemory {
+        size: u64,
+        memory_properties: MemoryPropertyFlags,
+    },
+    /// The buffer memory is not [`HOST_VISIBLE`](MemoryPropertyFlags::HOST_VISIBLE) and can not be
+    /// mapped.
+    #[error("Buffer memory is not host visible")]
+    NotHostVisible,
+}
 
 /// A struct to hold a vertex buffer.
-pub struct VertexBuffer<T>(Buffer<T>);
+pub struct VertexBuffer<T>(pub(crate) Buffer<T>);
 
 unsafe impl<T> Send for VertexBuffer<T> {}
 unsafe impl<T> Sync for VertexBuffer<T> {}
@@ -29,30 +54,7 @@ impl<T> VertexBuffer<T> {
     /// # Ok::<(), Box<dyn std::error::Error>>(())
     /// ```
     pub fn new(device: &Arc<Device>, data: &[T], cmd_pool: &CommandPool) -> Result<Self, Error> {
-        let size = (mem::size_of::<T>() * data.len()) as u64;
-        let staging = Buffer::new(
-            device,
-            data.len(),
-            vk::BufferUsageFlags::TRANSFER_SRC,
-            vk::SharingMode::EXCLUSIVE,
-            vk::MemoryPropertyFlags::HOST_VISIBLE | vk::MemoryPropertyFlags::HOST_COHERENT,
-        )?;
-
-        let mut mapped = staging.map()?;
-        mapped.write(data);
-        let staging = mapped.unmap();
-
-        let buffer = Buffer::new(
-            device,
-            data.len(),
-            vk::BufferUsageFlags::TRANSFER_DST | vk::BufferUsageFlags::VERTEX_BUFFER,
-            vk::SharingMode::EXCLUSIVE,
-            vk::MemoryPropertyFlags::DEVICE_LOCAL,
-        )?;
-
-        staging.copy_to(&buffer, size, cmd_pool)?;
-
-        Ok(Self(buffer))
+        Ok(Self(Buffer::new_staged(device, data, vk::BufferUsageFlags::VERTEX_BUFFER, cmd_pool)?))
     }
 
     /// Binds the VertexBuffer.
@@ -79,13 +81,47 @@ impl<T> VertexBuffer<T> {
     /// # Ok::<(), Box<dyn std::error::Error>>(())
     /// ```
     pub fn bind(&self, command_buffer: &CommandBuffer) {
+        self.bind_at(command_buffer, 0)
+    }
+
+    /// Binds the VertexBuffer at a specific vertex input binding.
+    ///
+    /// Used to bind multiple vertex buffers at once, such as a per vertex buffer at binding 0 and
+    /// a per instance buffer at binding 1, created with [`InputRate::INSTANCE`](crate::InputRate).
+    /// Each binding is independent and always reads its buffer from the start, binding a buffer
+    /// does not affect the buffers bound at other bindings.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # struct Vertex(f32);
+    /// # struct Instance(f32);
+    /// # let event_loop = winit::event_loop::EventLoop::new();
+    /// # let window = winit::window::WindowBuilder::new().build(&event_loop)?;
+    /// # let device = plate::Device::new(&Default::default(), &Default::default(), Some(&window))?;
+    /// # let cmd_pool = plate::CommandPool::new(&device)?;
+    /// # let cmd_buffer = cmd_pool.alloc_cmd_buffer(plate::CommandBufferLevel::PRIMARY)?;
+    /// # let vertices = [Vertex(0.0), Vertex(1.0)];
+    /// # let instances = [Instance(0.0), Instance(1.0)];
+    /// let vertex_buffer = plate::VertexBuffer::new(&device, &vertices, &cmd_pool)?;
+    /// let instance_buffer = plate::VertexBuffer::new(&device, &instances, &cmd_pool)?;
+    /// // cmd_buffer.record(.., || {
+    ///     // pipeline.bind(..);
+    ///     vertex_buffer.bind_at(&cmd_buffer, 0);
+    ///     instance_buffer.bind_at(&cmd_buffer, 1);
+    ///     cmd_buffer.draw(vertices.len() as u32, instances.len() as u32, 0, 0);
+    /// // })?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn bind_at(&self, command_buffer: &CommandBuffer, binding: u32) {
         let buffers = [self.0.buffer];
-        unsafe { self.0.device.cmd_bind_vertex_buffers(**command_buffer, 0, &buffers, &[0]) };
+        unsafe { self.0.device.cmd_bind_vertex_buffers(**command_buffer, binding, &buffers, &[0]) };
+        command_buffer.track_vertex_buffer(binding);
     }
 }
 
 /// A struct to hold a index buffer.
-pub struct IndexBuffer(Buffer<u32>);
+pub struct IndexBuffer(pub(crate) Buffer<u32>);
 
 unsafe impl Send for IndexBuffer {}
 unsafe impl Sync for IndexBuffer {}
@@ -105,30 +141,7 @@ impl IndexBuffer {
     /// # Ok::<(), Box<dyn std::error::Error>>(())
     /// ```
     pub fn new(device: &Arc<Device>, data: &[u32], cmd_pool: &CommandPool) -> Result<Self, Error> {
-        let size = (mem::size_of::<u32>() * data.len()) as u64;
-        let staging = Buffer::new(
-            device,
-            data.len(),
-            vk::BufferUsageFlags::TRANSFER_SRC,
-            vk::SharingMode::EXCLUSIVE,
-            vk::MemoryPropertyFlags::HOST_VISIBLE | vk::MemoryPropertyFlags::HOST_COHERENT,
-        )?;
-
-        let mut mapped = staging.map()?;
-        mapped.write(data);
-        let staging = mapped.unmap();
-
-        let buffer = Buffer::new(
-            device,
-            data.len(),
-            vk::BufferUsageFlags::TRANSFER_DST | vk::BufferUsageFlags::INDEX_BUFFER,
-            vk::SharingMode::EXCLUSIVE,
-            vk::MemoryPropertyFlags::DEVICE_LOCAL,
-        )?;
-
-        staging.copy_to(&buffer, size, cmd_pool)?;
-
-        Ok(Self(buffer))
+        Ok(Self(Buffer::new_staged(device, data, vk::BufferUsageFlags::INDEX_BUFFER, cmd_pool)?))
     }
 
     /// Binds the IndexBuffer.
@@ -164,6 +177,7 @@ impl IndexBuffer {
                 vk::IndexType::UINT32,
             )
         };
+        command_buffer.track_index_buffer();
     }
 }
 
@@ -207,6 +221,8 @@ impl<T> MappedBuffer<T> {
     ///
     /// The given slice must not have length greater than the `instance_count` parameter provided
     /// during the maped Buffer creation.
+    /// If the memory is not [`HOST_COHERENT`](MemoryPropertyFlags::HOST_COHERENT), the mapping
+    /// must be [`flushed`](Self::flush()) after writing for the device to see the new data.
     ///
     /// # Panics
     ///
@@ -266,14 +282,73 @@ impl<T> MappedBuffer<T> {
     pub fn write_index(&mut self, data: &[T], index: usize) {
         assert!(data.len()+index <= self.buffer.instance_count);
 
-        data.iter()
-            .enumerate()
-            .for_each(|(i, d)| {
-                unsafe {
-                    (d as *const T as *const u8)
-                        .copy_to_nonoverlapping((self.mapped as *mut u8).offset(((i+index) * self.buffer.alignment_size) as isize), mem::size_of::<T>())
-                }
-            });
+        unsafe { write_padded(self.mapped as *mut u8, data, index, self.buffer.alignment_size) };
+    }
+
+    /// Reads every instance of the mapped memory.
+    ///
+    /// The instances are padded to the Buffer alignment in memory, so they are copied to a new
+    /// `Vec` instead of being returned as a slice. If the memory is not
+    /// [`HOST_COHERENT`](MemoryPropertyFlags::HOST_COHERENT) it is
+    /// [`invalidated`](Self::invalidate()) first, to make device writes visible to the host.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # let event_loop = winit::event_loop::EventLoop::new();
+    /// # let window = winit::window::WindowBuilder::new().build(&event_loop)?;
+    /// # let device = plate::Device::new(&Default::default(), &Default::default(), Some(&window))?;
+    /// let buffer: plate::Buffer<u32> = plate::Buffer::new(&device, 4, // ..
+    ///     # plate::BufferUsageFlags::STORAGE_BUFFER,
+    ///     # plate::SharingMode::EXCLUSIVE,
+    ///     # plate::MemoryPropertyFlags::HOST_VISIBLE,
+    /// # )?;
+    /// let mapped = buffer.map()?;
+    /// let data = mapped.read()?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn read(&self) -> Result<Vec<T>, Error> where T: Copy {
+        if !self.is_coherent() {
+            self.invalidate()?;
+        }
+
+        Ok((0..self.buffer.instance_count)
+            .map(|i| unsafe { read_padded(self.mapped as *const u8, i, self.buffer.alignment_size) })
+            .collect())
+    }
+
+    /// Reads the instance at `index` from the mapped memory.
+    ///
+    /// If the memory is not [`HOST_COHERENT`](MemoryPropertyFlags::HOST_COHERENT) the range of
+    /// the instance is [`invalidated`](Self::invalidate_index()) first.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index is larger than the buffer capacity.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # let event_loop = winit::event_loop::EventLoop::new();
+    /// # let window = winit::window::WindowBuilder::new().build(&event_loop)?;
+    /// # let device = plate::Device::new(&Default::default(), &Default::default(), Some(&window))?;
+    /// let buffer: plate::Buffer<u32> = plate::Buffer::new(&device, 4, // ..
+    ///     # plate::BufferUsageFlags::STORAGE_BUFFER,
+    ///     # plate::SharingMode::EXCLUSIVE,
+    ///     # plate::MemoryPropertyFlags::HOST_VISIBLE,
+    /// # )?;
+    /// let mapped = buffer.map()?;
+    /// let value = mapped.read_at(2)?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn read_at(&self, index: usize) -> Result<T, Error> where T: Copy {
+        assert!(index < self.buffer.instance_count);
+
+        if !self.is_coherent() {
+            self.invalidate_index(index, 1)?;
+        }
+
+        Ok(unsafe { read_padded(self.mapped as *const u8, index, self.buffer.alignment_size) })
     }
 
     /// Flushes a this Buffer mapped memory.
@@ -317,22 +392,218 @@ impl<T> MappedBuffer<T> {
     /// # Ok::<(), Box<dyn std::error::Error>>(())
     /// ```
     pub fn flush_index(&self, offset: usize, size: usize) -> Result<(), Error> {
+        let range = self.memory_range(offset, size);
+        unsafe { self.buffer.device.flush_mapped_memory_ranges(&[range])? };
+        Ok(())
+    }
+
+    /// Invalidates this Buffer mapped memory, making device writes visible to the host.
+    ///
+    /// Only needed for memory without [`HOST_COHERENT`](MemoryPropertyFlags::HOST_COHERENT),
+    /// [`read()`](Self::read()) and [`read_at()`](Self::read_at()) already invalidate the memory
+    /// they read.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # let event_loop = winit::event_loop::EventLoop::new();
+    /// # let window = winit::window::WindowBuilder::new().build(&event_loop)?;
+    /// # let device = plate::Device::new(&Default::default(), &Default::default(), Some(&window))?;
+    /// let buffer: plate::Buffer<u32> = plate::Buffer::new(&device, 4, // ..
+    ///     # plate::BufferUsageFlags::STORAGE_BUFFER,
+    ///     # plate::SharingMode::EXCLUSIVE,
+    ///     # plate::MemoryPropertyFlags::HOST_VISIBLE,
+    /// # )?;
+    /// let mapped = buffer.map()?;
+    /// mapped.invalidate()?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn invalidate(&self) -> Result<(), Error> { self.invalidate_index(0, self.buffer.instance_count) }
+
+    /// Invalidates a range of this Buffer mapped memory.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the offset + size is greater than the Buffer capacity.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # let event_loop = winit::event_loop::EventLoop::new();
+    /// # let window = winit::window::WindowBuilder::new().build(&event_loop)?;
+    /// # let device = plate::Device::new(&Default::default(), &Default::default(), Some(&window))?;
+    /// let buffer: plate::Buffer<u32> = plate::Buffer::new(&device, 4, // ..
+    ///     # plate::BufferUsageFlags::STORAGE_BUFFER,
+    ///     # plate::SharingMode::EXCLUSIVE,
+    ///     # plate::MemoryPropertyFlags::HOST_VISIBLE,
+    /// # )?;
+    /// let mapped = buffer.map()?;
+    /// mapped.invalidate_index(2, 1)?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn invalidate_index(&self, offset: usize, size: usize) -> Result<(), Error> {
+        let range = self.memory_range(offset, size);
+        unsafe { self.buffer.device.invalidate_mapped_memory_ranges(&[range])? };
+        Ok(())
+    }
+
+    fn is_coherent(&self) -> bool {
+        self.buffer.memory_properties.contains(MemoryPropertyFlags::HOST_COHERENT)
+    }
+
+    fn memory_range(&self, offset: usize, size: usize) -> vk::MappedMemoryRange {
         assert!(offset+size <= self.buffer.instance_count);
 
-        let range = vk::MappedMemoryRange::builder()
+        let limits = unsafe { self.buffer.device.instance.get_physical_device_properties(self.buffer.device.physical_device).limits };
+        let (offset, size) = atom_range(
+            self.buffer.alignment_size * offset,
+            self.buffer.alignment_size * (offset+size),
+            self.buffer.alignment_size * self.buffer.instance_count,
+            limits.non_coherent_atom_size as usize,
+        );
+
+        *vk::MappedMemoryRange::builder()
             .memory(self.buffer.mem)
-            .offset((self.buffer.alignment_size * offset) as u64)
-            .size((self.buffer.alignment_size * size) as u64);
+            .offset(offset)
+            .size(size)
+    }
+}
 
-        Ok(unsafe { self.buffer.device.flush_mapped_memory_ranges(&[*range])? })
+/// A host visible buffer with a separate region for each frame in flight.
+///
+/// Writing to a buffer the GPU may still be reading from a previous frame is a data race. The
+/// PerFrameBuffer holds `frames_in_flight` copies of `instance_count` instances, padded to the
+/// device dynamic offset alignment, so each frame writes to its own region. Bind it to a
+/// [`UNIFORM_BUFFER_DYNAMIC`](crate::DescriptorType::UNIFORM_BUFFER_DYNAMIC) or
+/// [`STORAGE_BUFFER_DYNAMIC`](crate::DescriptorType::STORAGE_BUFFER_DYNAMIC) descriptor with
+/// [`DescriptorAllocator::add_dynamic_buffer_binding()`](crate::DescriptorAllocator::add_dynamic_buffer_binding()) and pass
+/// [`dynamic_offset()`](Self::dynamic_offset()) when binding the
+/// [`DescriptorSet`](crate::DescriptorSet).
+pub struct PerFrameBuffer<T> {
+    mapped: MappedBuffer<T>,
+    instance_count: usize,
+    frames_in_flight: usize,
+}
+
+impl<T> PerFrameBuffer<T> {
+    /// Creates a PerFrameBuffer with `instance_count` instances for each of the
+    /// `frames_in_flight`.
+    ///
+    /// The memory is persistently mapped and host coherent.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # let event_loop = winit::event_loop::EventLoop::new();
+    /// # let window = winit::window::WindowBuilder::new().build(&event_loop)?;
+    /// # let device = plate::Device::new(&Default::default(), &Default::default(), Some(&window))?;
+    /// // A uniform buffer with 1 instance for each of 2 frames in flight
+    /// let ubo: plate::PerFrameBuffer<u32> = plate::PerFrameBuffer::new(
+    ///     &device,
+    ///     1,
+    ///     2,
+    ///     plate::BufferUsageFlags::UNIFORM_BUFFER,
+    /// )?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn new(device: &Arc<Device>, instance_count: usize, frames_in_flight: usize, usage: BufferUsageFlags) -> Result<Self, Error> {
+        let buffer = Buffer::new(
+            device,
+            instance_count * frames_in_flight,
+            usage,
+            SharingMode::EXCLUSIVE,
+            MemoryPropertyFlags::HOST_VISIBLE | MemoryPropertyFlags::HOST_COHERENT,
+        )?;
+
+        Ok(Self {
+            mapped: buffer.map()?,
+            instance_count,
+            frames_in_flight,
+        })
+    }
+
+    /// Writes data from a slice to the region of `frame`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `frame` is not less than the number of frames in flight or the data is larger
+    /// than the instance count of a frame.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # let event_loop = winit::event_loop::EventLoop::new();
+    /// # let window = winit::window::WindowBuilder::new().build(&event_loop)?;
+    /// # let device = plate::Device::new(&Default::default(), &Default::default(), Some(&window))?;
+    /// # let mut ubo: plate::PerFrameBuffer<u32> = plate::PerFrameBuffer::new(&device, 1, 2,
+    /// # plate::BufferUsageFlags::UNIFORM_BUFFER)?;
+    /// let frame = 1;
+    /// ubo.write(frame, &[42]);
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn write(&mut self, frame: usize, data: &[T]) {
+        assert!(frame < self.frames_in_flight);
+        assert!(data.len() <= self.instance_count);
+
+        self.mapped.write_index(data, frame * self.instance_count);
+    }
+
+    /// Returns the dynamic offset of the region of `frame`, to be passed to
+    /// [`DescriptorSet::bind()`](crate::DescriptorSet::bind()).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `frame` is not less than the number of frames in flight.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # let event_loop = winit::event_loop::EventLoop::new();
+    /// # let window = winit::window::WindowBuilder::new().build(&event_loop)?;
+    /// # let device = plate::Device::new(&Default::default(), &Default::default(), Some(&window))?;
+    /// # let cmd_pool = plate::CommandPool::new(&device)?;
+    /// # let cmd_buffer = cmd_pool.alloc_cmd_buffer(plate::CommandBufferLevel::PRIMARY)?;
+    /// # let render_pass = plate::RenderPass::new(&device, &[], &[], &[])?;
+    /// # let pipeline = plate::pipeline::Pipeline::new(&device, &render_pass, &[], &[],
+    /// # &Default::default())?;
+    /// # let layout = plate::DescriptorSetLayout::new(&device, &[])?;
+    /// # let pool = plate::DescriptorPool::new(&device, &[], 2)?;
+    /// # let ubo: plate::PerFrameBuffer<u32> = plate::PerFrameBuffer::new(&device, 1, 2,
+    /// # plate::BufferUsageFlags::UNIFORM_BUFFER)?;
+    /// let descriptor_set = plate::DescriptorAllocator::new(&device)
+    ///     .add_dynamic_buffer_binding(0, 1, plate::DescriptorType::UNIFORM_BUFFER_DYNAMIC, ubo.buffer())
+    ///     .allocate(&layout, &pool)?;
+    /// let frame = 1;
+    /// // cmd_buffer.record(.., || {
+    ///     descriptor_set.bind(&cmd_buffer, &pipeline, 0, &[ubo.dynamic_offset(frame)])?;
+    /// // })?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn dynamic_offset(&self, frame: usize) -> u32 {
+        assert!(frame < self.frames_in_flight);
+        (frame * self.instance_count) as u32
+    }
+
+    /// Returns the underlying [`Buffer`], holding the regions of all frames.
+    ///
+    /// When binding it to a descriptor, only the instances of a single frame should be bound, see
+    /// [`DescriptorAllocator::add_buffer_index_binding()`](crate::DescriptorAllocator::add_buffer_index_binding()).
+    pub fn buffer(&self) -> &Buffer<T> {
+        &self.mapped.buffer
+    }
+
+    /// Returns the number of frames in flight.
+    pub fn frames_in_flight(&self) -> usize {
+        self.frames_in_flight
     }
 }
 
 /// A struct containing a vk::Buffer.
 pub struct Buffer<T> {
     device: Arc<Device>,
-    buffer: vk::Buffer,
+    pub(crate) buffer: vk::Buffer,
     mem: vk::DeviceMemory,
+    memory_properties: MemoryPropertyFlags,
     pub(crate) instance_count: usize,
     pub(crate) alignment_size: usize,
 
@@ -351,9 +622,19 @@ impl<T> Drop for Buffer<T> {
     }
 }
 
+impl<T> DebugObject for Buffer<T> {
+    fn debug_handle(&self) -> (ObjectType, u64) {
+        (ObjectType::BUFFER, vk::Handle::as_raw(self.buffer))
+    }
+}
+
 impl<T> Buffer<T> {
     /// Creates a Buffer\<T\>.
     ///
+    /// Returns [`BufferError::InvalidMemoryProperties`] if `memory_properties` is not a valid
+    /// combination, [`BufferError::NoSuitableMemoryType`] if no memory type of the device supports
+    /// it and [`BufferError::OutOfMemory`] if the allocation fails.
+    ///
     /// # Examples
     ///
     /// ```no_run
@@ -377,6 +658,12 @@ impl<T> Buffer<T> {
         sharing_mode: SharingMode,
         memory_properties: MemoryPropertyFlags,
     ) -> Result<Self, Error> {
+        if memory_properties.contains(MemoryPropertyFlags::HOST_COHERENT)
+            && !memory_properties.contains(MemoryPropertyFlags::HOST_VISIBLE)
+        {
+            return Err(BufferError::InvalidMemoryProperties(memory_properties).into());
+        }
+
         let alignment_size = alignment::<T>(device, usage);
         let size = alignment_size * instance_count;
 
@@ -388,19 +675,44 @@ impl<T> Buffer<T> {
         let buffer = unsafe { device.create_buffer(&buffer_info, None)? };
 
         let mem_requirements = unsafe { device.get_buffer_memory_requirements(buffer) };
-        let mem_type_index = device.memory_type_index(mem_requirements, memory_properties)?;
+        let mem_type_index = match device.memory_type_index(mem_requirements, memory_properties) {
+            Some(index) => index,
+            None => {
+                unsafe { device.destroy_buffer(buffer, None) };
+                return Err(BufferError::NoSuitableMemoryType(memory_properties).into());
+            }
+        };
 
         let alloc_info = vk::MemoryAllocateInfo::builder()
             .allocation_size(mem_requirements.size)
             .memory_type_index(mem_type_index as u32);
 
-        let mem = unsafe { device.allocate_memory(&alloc_info, None)? };
-        unsafe { device.bind_buffer_memory(buffer, mem, 0)? };
+        let mem = match unsafe { device.allocate_memory(&alloc_info, None) } {
+            Ok(mem) => mem,
+            Err(e) => {
+                unsafe { device.destroy_buffer(buffer, None) };
+                return Err(match e {
+                    vk::Result::ERROR_OUT_OF_DEVICE_MEMORY | vk::Result::ERROR_OUT_OF_HOST_MEMORY => BufferError::OutOfMemory {
+                        size: mem_requirements.size,
+                        memory_properties,
+                    }.into(),
+                    e => e.into(),
+                });
+            }
+        };
+        if let Err(e) = unsafe { device.bind_buffer_memory(buffer, mem, 0) } {
+            unsafe {
+                device.free_memory(mem, None);
+                device.destroy_buffer(buffer, None);
+            }
+            return Err(e.into());
+        }
 
         Ok(Self {
             device: Arc::clone(&device),
             buffer,
             mem,
+            memory_properties,
             instance_count,
             alignment_size,
 
@@ -408,7 +720,68 @@ impl<T> Buffer<T> {
         })
     }
 
+    /// Creates a [`DEVICE_LOCAL`](MemoryPropertyFlags::DEVICE_LOCAL) Buffer\<T\> filled with data
+    /// from a slice.
+    ///
+    /// The data is written to a temporary host visible staging buffer and copied to the new buffer,
+    /// blocking until the copy is done. Prefer this over a host visible buffer for large data that
+    /// does not change, such as meshes.
+    ///
+    /// If the Device has a dedicated transfer queue, see [`Device::has_transfer_queue()`], the copy
+    /// runs on it and the ownership of the buffer is transferred to the graphics queue family.
+    /// Otherwise the copy is recorded to a command buffer from `cmd_pool` and runs on the graphics
+    /// queue. [`UploadContext`](crate::UploadContext) batches uploads without blocking.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # let event_loop = winit::event_loop::EventLoop::new();
+    /// # let window = winit::window::WindowBuilder::new().build(&event_loop)?;
+    /// # let device = plate::Device::new(&Default::default(), &Default::default(), Some(&window))?;
+    /// # let cmd_pool = plate::CommandPool::new(&device)?;
+    /// let data = [1u32, 2, 3, 4];
+    /// let buffer = plate::Buffer::new_staged(&device, &data, plate::BufferUsageFlags::STORAGE_BUFFER, &cmd_pool)?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn new_staged(device: &Arc<Device>, data: &[T], usage: BufferUsageFlags, cmd_pool: &CommandPool) -> Result<Self, Error> {
+        if device.has_transfer_queue() {
+            let mut upload = UploadContext::new(device)?;
+            let buffer = upload.buffer(data, usage)?;
+            upload.submit()?.wait()?;
+            return Ok(buffer)
+        }
+
+        // Created with the same usage so both buffers share the instance alignment
+        let staging = Buffer::new(
+            device,
+            data.len(),
+            usage | vk::BufferUsageFlags::TRANSFER_SRC,
+            vk::SharingMode::EXCLUSIVE,
+            vk::MemoryPropertyFlags::HOST_VISIBLE | vk::MemoryPropertyFlags::HOST_COHERENT,
+        )?;
+
+        let mut mapped = staging.map()?;
+        mapped.write(data);
+        let staging = mapped.unmap();
+
+        let buffer = Buffer::new(
+            device,
+            data.len(),
+            usage | vk::BufferUsageFlags::TRANSFER_DST,
+            vk::SharingMode::EXCLUSIVE,
+            vk::MemoryPropertyFlags::DEVICE_LOCAL,
+        )?;
+
+        let size = (staging.alignment_size * staging.instance_count) as u64;
+        staging.copy_to(&buffer, size, cmd_pool)?;
+
+        Ok(buffer)
+    }
+
     /// Maps the memory the host and returns a [`MappedBuffer`].
+    ///
+    /// Returns [`BufferError::NotHostVisible`] if the buffer memory is not
+    /// [`HOST_VISIBLE`](MemoryPropertyFlags::HOST_VISIBLE).
     /// 
     /// # Example
     /// 
@@ -427,6 +800,10 @@ impl<T> Buffer<T> {
     /// # Ok::<(), Box<dyn std::error::Error>>(())
     /// ```
     pub fn map(self) -> Result<MappedBuffer<T>, Error> {
+        if !self.memory_properties.contains(MemoryPropertyFlags::HOST_VISIBLE) {
+            return Err(BufferError::NotHostVisible.into());
+        }
+
         let mapped = unsafe {
             self.device.map_memory(
                 self.mem,
@@ -460,7 +837,64 @@ impl<T> Buffer<T> {
         Ok(unsafe { self.device.queue_wait_idle(self.device.queue.queue)? })
     }
 
-    pub(crate) fn copy_to_image(&self, image: vk::Image, width: u32, height: u32, cmd_pool: &CommandPool) -> Result<(), Error> {
+    /// Creates a host visible staging buffer holding `data`, laid out to be copied to this
+    /// Buffer with [`record_copy_from()`](Self::record_copy_from()).
+    pub(crate) fn staging(&self, data: &[T]) -> Result<Buffer<u8>, Error> {
+        let staging = Buffer::<u8>::new(
+            &self.device,
+            self.alignment_size * self.instance_count,
+            vk::BufferUsageFlags::TRANSFER_SRC,
+            vk::SharingMode::EXCLUSIVE,
+            vk::MemoryPropertyFlags::HOST_VISIBLE | vk::MemoryPropertyFlags::HOST_COHERENT,
+        )?;
+
+        let mapped = staging.map()?;
+        unsafe { write_padded(mapped.mapped as *mut u8, data, 0, self.alignment_size) };
+        Ok(mapped.unmap())
+    }
+
+    /// Records a copy of the whole `staging` buffer to this Buffer.
+    pub(crate) fn record_copy_from(&self, cmd_buffer: &CommandBuffer, staging: &Buffer<u8>) {
+        let regions = [*vk::BufferCopy::builder().size((staging.alignment_size * staging.instance_count) as u64)];
+        unsafe { self.device.cmd_copy_buffer(**cmd_buffer, staging.buffer, self.buffer, &regions) };
+    }
+
+    /// Copies `array_layers` tightly packed layers, one after the other, to the first mip level of
+    /// `image`.
+    pub(crate) fn copy_to_image(&self, image: vk::Image, width: u32, height: u32, array_layers: u32, cmd_pool: &CommandPool) -> Result<(), Error> {
+        let cmd_buffer = cmd_pool.alloc_cmd_buffer(CommandBufferLevel::PRIMARY)?;
+        cmd_buffer.record(CommandBufferUsageFlags::ONE_TIME_SUBMIT, || {
+            self.record_copy_to_image(&cmd_buffer, image, width, height, array_layers);
+        })?;
+
+        self.device.queue_submit(&cmd_buffer, PipelineStage::empty(), None, None, None)?;
+        Ok(unsafe { self.device.queue_wait_idle(self.device.queue.queue)? })
+    }
+
+    /// Records a copy of `array_layers` tightly packed layers to the first mip level of `image`,
+    /// which must be in the TRANSFER_DST_OPTIMAL layout.
+    pub(crate) fn record_copy_to_image(&self, cmd_buffer: &CommandBuffer, image: vk::Image, width: u32, height: u32, array_layers: u32) {
+        let region = vk::BufferImageCopy::builder()
+            .buffer_offset(0)
+            .buffer_row_length(0)
+            .buffer_image_height(0)
+            .image_subresource(vk::ImageSubresourceLayers {
+                aspect_mask: vk::ImageAspectFlags::COLOR,
+                mip_level: 0,
+                base_array_layer: 0,
+                layer_count: array_layers,
+            })
+            .image_offset(vk::Offset3D { x: 0, y: 0, z: 0 })
+            .image_extent(vk::Extent3D { 
+                width,
+                height,
+                depth: 1,
+            });
+
+        unsafe { self.device.cmd_copy_buffer_to_image(**cmd_buffer, self.buffer, image, vk::ImageLayout::TRANSFER_DST_OPTIMAL, &[*region]) };
+    }
+
+    pub(crate) fn copy_from_image(&self, image: vk::Image, width: u32, height: u32, cmd_pool: &CommandPool) -> Result<(), Error> {
         let cmd_buffer = cmd_pool.alloc_cmd_buffer(CommandBufferLevel::PRIMARY)?;
         cmd_buffer.record(CommandBufferUsageFlags::ONE_TIME_SUBMIT, || {
             let region = vk::BufferImageCopy::builder()
@@ -474,13 +908,13 @@ impl<T> Buffer<T> {
                     layer_count: 1,
                 })
                 .image_offset(vk::Offset3D { x: 0, y: 0, z: 0 })
-                .image_extent(vk::Extent3D { 
+                .image_extent(vk::Extent3D {
                     width,
                     height,
                     depth: 1,
                 });
 
-            unsafe { self.device.cmd_copy_buffer_to_image(*cmd_buffer, self.buffer, image, vk::ImageLayout::TRANSFER_DST_OPTIMAL, &[*region]) };
+            unsafe { self.device.cmd_copy_image_to_buffer(*cmd_buffer, image, vk::ImageLayout::TRANSFER_SRC_OPTIMAL, self.buffer, &[*region]) };
         })?;
 
         self.device.queue_submit(&cmd_buffer, PipelineStage::empty(), None, None, None)?;
@@ -495,10 +929,42 @@ impl<T> Buffer<T> {
     }
 }
 
+/// Copies `data` to `dst`, starting at instance `index`, with each instance `alignment` bytes
+/// apart.
+unsafe fn write_padded<T>(dst: *mut u8, data: &[T], index: usize, alignment: usize) {
+    data.iter()
+        .enumerate()
+        .for_each(|(i, d)| {
+            (d as *const T as *const u8)
+                .copy_to_nonoverlapping(dst.add((i+index) * alignment), mem::size_of::<T>())
+        });
+}
+
+/// Reads the instance at `index` from `src`, with each instance `alignment` bytes apart.
+unsafe fn read_padded<T: Copy>(src: *const u8, index: usize, alignment: usize) -> T {
+    (src.add(index * alignment) as *const T).read_unaligned()
+}
+
+/// Returns the offset and size of a mapped memory range covering the bytes from `start` to
+/// `end`, expanded to multiples of `atom_size` as required for flushes and invalidations.
+/// Ranges reaching the end of the buffer extend to the end of the allocation.
+fn atom_range(start: usize, end: usize, buffer_size: usize, atom_size: usize) -> (u64, u64) {
+    let atom_size = atom_size.max(1);
+    let offset = start / atom_size * atom_size;
+    let end = end.div_ceil(atom_size) * atom_size;
+
+    match end >= buffer_size {
+        true => (offset as u64, vk::WHOLE_SIZE),
+        false => (offset as u64, (end - offset) as u64),
+    }
+}
+
 fn alignment<T>(device: &Arc<Device>, usage: BufferUsageFlags) -> usize {
     let limits = unsafe { device.instance.get_physical_device_properties(device.physical_device).limits };
     let min_offset = if usage.contains(BufferUsageFlags::UNIFORM_BUFFER) {
         limits.min_uniform_buffer_offset_alignment.max(limits.non_coherent_atom_size)
+    } else if usage.contains(BufferUsageFlags::STORAGE_BUFFER) {
+        limits.min_storage_buffer_offset_alignment.max(limits.non_coherent_atom_size)
     } else { 1 } as usize;
 
     let instance_size = mem::size_of::<T>();
@@ -506,3 +972,31 @@ fn alignment<T>(device: &Arc<Device>, usage: BufferUsageFlags) -> usize {
         (instance_size + min_offset - 1) & !(min_offset - 1)
     } else { instance_size }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_padded_round_trip() {
+        let alignment = 16;
+        let mut memory = vec![0u8; 4 * alignment];
+        let data = [1u32, 2, 3];
+
+        unsafe { write_padded(memory.as_mut_ptr(), &data, 1, alignment) };
+        let read = (0..4)
+            .map(|i| unsafe { read_padded::<u32>(memory.as_ptr(), i, alignment) })
+            .collect::<Vec<_>>();
+
+        assert_eq!(read, [0, 1, 2, 3]);
+        assert!(memory[alignment+4..2*alignment].iter().all(|b| *b == 0));
+    }
+
+    #[test]
+    fn test_atom_range() {
+        assert_eq!(atom_range(16, 20, 64, 64), (0, vk::WHOLE_SIZE));
+        assert_eq!(atom_range(72, 80, 256, 64), (64, 64));
+        assert_eq!(atom_range(60, 70, 256, 64), (0, 128));
+        assert_eq!(atom_range(4, 8, 256, 1), (4, 4));
+    }
+}
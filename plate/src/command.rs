@@ -2,12 +2,35 @@ use std::sync::Arc;
 
 use ash::vk;
 
-use crate::{Device, Error};
+use crate::{AccessFlags, Buffer, DebugObject, Device, Error, Framebuffer, Image, ImageAspectFlags, ImageLayout, ObjectType, PipelineStage, QueryPool, RenderPass};
 
 pub use vk::CommandBufferLevel as CommandBufferLevel;
 pub use vk::CommandBufferUsageFlags as CommandBufferUsageFlags;
 
+/// Errors from the command module.
+#[derive(thiserror::Error, Debug)]
+pub enum CommandError {
+    /// A draw was recorded without a binding it requires. Only checked in debug builds.
+    #[error("Draw recorded without a bound {0:?}")]
+    MissingBinding(DrawBinding),
+}
+
+/// A binding required to record a draw, see [`CommandError::MissingBinding`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DrawBinding {
+    /// A [`Pipeline`](crate::Pipeline).
+    Pipeline,
+    /// A [`VertexBuffer`](crate::VertexBuffer) at the vertex input binding, required by the
+    /// bound pipeline.
+    VertexBuffer(u32),
+    /// An [`IndexBuffer`](crate::IndexBuffer), required by indexed draws.
+    IndexBuffer,
+}
+
 /// Holds a [`vk::CommandPool`], used to allocate [`CommandBuffers`](CommandBuffer).
+///
+/// A CommandPool and the CommandBuffers allocated from it must not be used from multiple threads
+/// at the same time. To record command buffers in parallel, create one CommandPool per thread.
 pub struct CommandPool {
     device: Arc<Device>,
     cmd_pool: vk::CommandPool,
@@ -42,9 +65,32 @@ impl CommandPool {
     /// # Ok::<(), Box<dyn std::error::Error>>(())
     /// ```
     pub fn new(device: &Arc<Device>) -> Result<Self, Error> {
+        Self::with_family(device, device.queue.family)
+    }
+
+    /// Creates a CommandPool for the transfer queue of the device, see
+    /// [`Device::transfer_queue_family()`].
+    ///
+    /// CommandBuffers allocated from it can only be submitted with
+    /// [`Device::queue_submit_transfer()`], and only record transfer commands.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # let event_loop = winit::event_loop::EventLoop::new();
+    /// # let window = winit::window::WindowBuilder::new().build(&event_loop)?;
+    /// # let device = plate::Device::new(&Default::default(), &Default::default(), Some(&window))?;
+    /// let transfer_pool = plate::CommandPool::new_transfer(&device)?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn new_transfer(device: &Arc<Device>) -> Result<Self, Error> {
+        Self::with_family(device, device.transfer_queue.family)
+    }
+
+    fn with_family(device: &Arc<Device>, family: u32) -> Result<Self, Error> {
         let pool_info = vk::CommandPoolCreateInfo::builder()
             .flags(vk::CommandPoolCreateFlags::RESET_COMMAND_BUFFER)
-            .queue_family_index(device.queue.family);
+            .queue_family_index(family);
 
         let cmd_pool = unsafe { device.create_command_pool(&pool_info, None)? };
 
@@ -74,7 +120,12 @@ impl CommandPool {
 
         let cmd_buffers = unsafe { self.device.allocate_command_buffers(&alloc_info)? };
         Ok(cmd_buffers.into_iter()
-            .map(|cmd_buffer| CommandBuffer { device: Arc::clone(&self.device), cmd_buffer })
+            .map(|cmd_buffer| CommandBuffer {
+                device: Arc::clone(&self.device),
+                cmd_buffer,
+                #[cfg(debug_assertions)]
+                draw_state: Default::default(),
+            })
             .collect())
     }
 
@@ -95,10 +146,253 @@ impl CommandPool {
     }
 }
 
+/// Describes the [`RenderPass`] a secondary [`CommandBuffer`] will be executed in.
+pub struct RenderPassInheritance<'a> {
+    /// RenderPass the secondary command buffer will be executed in.
+    pub render_pass: &'a RenderPass,
+    /// Index of the subpass the secondary command buffer will be executed in.
+    pub subpass: u32,
+    /// Framebuffer the secondary command buffer will be executed with, if known.
+    pub framebuffer: Option<&'a Framebuffer>,
+}
+
+/// A barrier for a range of a [`Buffer`], used with [`CommandBuffer::pipeline_barrier()`].
+///
+/// Makes the `src_access` writes to the buffer available to the `dst_access` operations.
+///
+/// # Examples
+///
+/// ```no_run
+/// # let event_loop = winit::event_loop::EventLoop::new();
+/// # let window = winit::window::WindowBuilder::new().build(&event_loop)?;
+/// # let device = plate::Device::new(&Default::default(), &Default::default(), Some(&window))?;
+/// # let buffer: plate::Buffer<u32> = plate::Buffer::new(
+///     # &device,
+///     # 1,
+///     # plate::BufferUsageFlags::STORAGE_BUFFER,
+///     # plate::SharingMode::EXCLUSIVE,
+///     # plate::MemoryPropertyFlags::DEVICE_LOCAL,
+/// # )?;
+/// let barrier = plate::BufferMemoryBarrier::new(&buffer)
+///     .src_access(plate::AccessFlags::SHADER_WRITE)
+///     .dst_access(plate::AccessFlags::VERTEX_ATTRIBUTE_READ);
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Clone, Copy)]
+pub struct BufferMemoryBarrier {
+    buffer: vk::Buffer,
+    src_access: AccessFlags,
+    dst_access: AccessFlags,
+    offset: u64,
+    size: u64,
+    src_queue_family: u32,
+    dst_queue_family: u32,
+}
+
+impl BufferMemoryBarrier {
+    /// Creates a BufferMemoryBarrier covering the whole buffer, with empty access masks.
+    pub fn new<T>(buffer: &Buffer<T>) -> Self {
+        Self {
+            buffer: buffer.buffer,
+            src_access: AccessFlags::empty(),
+            dst_access: AccessFlags::empty(),
+            offset: 0,
+            size: vk::WHOLE_SIZE,
+            src_queue_family: vk::QUEUE_FAMILY_IGNORED,
+            dst_queue_family: vk::QUEUE_FAMILY_IGNORED,
+        }
+    }
+
+    /// Sets the accesses that must complete before the barrier.
+    pub fn src_access(mut self, access: AccessFlags) -> Self {
+        self.src_access = access;
+        self
+    }
+
+    /// Sets the accesses that must wait for the barrier.
+    pub fn dst_access(mut self, access: AccessFlags) -> Self {
+        self.dst_access = access;
+        self
+    }
+
+    /// Restricts the barrier to `size` bytes starting `offset` bytes into the buffer.
+    pub fn range(mut self, offset: u64, size: u64) -> Self {
+        self.offset = offset;
+        self.size = size;
+        self
+    }
+
+    /// Transfers the ownership of the buffer from the `src` queue family to the `dst` queue
+    /// family.
+    ///
+    /// The same barrier must be recorded to a command buffer of each queue family, first to
+    /// release the buffer and then to acquire it.
+    pub fn queue_families(mut self, src: u32, dst: u32) -> Self {
+        self.src_queue_family = src;
+        self.dst_queue_family = dst;
+        self
+    }
+}
+
+/// A barrier for a subresource range of an [`Image`], used with
+/// [`CommandBuffer::pipeline_barrier()`].
+///
+/// Makes the `src_access` writes to the image available to the `dst_access` operations, and
+/// transitions the image from the old layout to the new one.
+///
+/// # Examples
+///
+/// ```no_run
+/// # let event_loop = winit::event_loop::EventLoop::new();
+/// # let window = winit::window::WindowBuilder::new().build(&event_loop)?;
+/// # let device = plate::Device::new(&Default::default(), &Default::default(), Some(&window))?;
+/// # let image = plate::Image::new(&device, 0, 0,
+/// # plate::Format::UNDEFINED, plate::ImageLayout::UNDEFINED,
+/// # plate::ImageUsageFlags::empty(), plate::ImageAspectFlags::empty())?;
+/// let barrier = plate::ImageMemoryBarrier::new(&image)
+///     .src_access(plate::AccessFlags::COLOR_ATTACHMENT_WRITE)
+///     .dst_access(plate::AccessFlags::SHADER_READ)
+///     .layouts(plate::ImageLayout::COLOR_ATTACHMENT_OPTIMAL, plate::ImageLayout::SHADER_READ_ONLY_OPTIMAL);
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Clone, Copy)]
+pub struct ImageMemoryBarrier {
+    image: vk::Image,
+    src_access: AccessFlags,
+    dst_access: AccessFlags,
+    old_layout: ImageLayout,
+    new_layout: ImageLayout,
+    range: vk::ImageSubresourceRange,
+    src_queue_family: u32,
+    dst_queue_family: u32,
+}
+
+impl ImageMemoryBarrier {
+    /// Creates a ImageMemoryBarrier covering all the mip levels and layers of the image, with
+    /// empty access masks and without a layout transition.
+    pub fn new(image: &Image) -> Self {
+        Self {
+            image: image.image,
+            src_access: AccessFlags::empty(),
+            dst_access: AccessFlags::empty(),
+            old_layout: ImageLayout::UNDEFINED,
+            new_layout: ImageLayout::UNDEFINED,
+            range: vk::ImageSubresourceRange {
+                aspect_mask: image.aspect,
+                base_mip_level: 0,
+                level_count: vk::REMAINING_MIP_LEVELS,
+                base_array_layer: 0,
+                layer_count: vk::REMAINING_ARRAY_LAYERS,
+            },
+            src_queue_family: vk::QUEUE_FAMILY_IGNORED,
+            dst_queue_family: vk::QUEUE_FAMILY_IGNORED,
+        }
+    }
+
+    /// Sets the accesses that must complete before the barrier.
+    pub fn src_access(mut self, access: AccessFlags) -> Self {
+        self.src_access = access;
+        self
+    }
+
+    /// Sets the accesses that must wait for the barrier.
+    pub fn dst_access(mut self, access: AccessFlags) -> Self {
+        self.dst_access = access;
+        self
+    }
+
+    /// Sets the layout transition of the barrier.
+    ///
+    /// The contents of the image are discarded if `old_layout` is
+    /// [`UNDEFINED`](ImageLayout::UNDEFINED).
+    pub fn layouts(mut self, old_layout: ImageLayout, new_layout: ImageLayout) -> Self {
+        self.old_layout = old_layout;
+        self.new_layout = new_layout;
+        self
+    }
+
+    /// Restricts the barrier to the aspects in `aspect`.
+    pub fn aspect(mut self, aspect: ImageAspectFlags) -> Self {
+        self.range.aspect_mask = aspect;
+        self
+    }
+
+    /// Restricts the barrier to `level_count` mip levels starting at `base_mip_level`.
+    pub fn mip_levels(mut self, base_mip_level: u32, level_count: u32) -> Self {
+        self.range.base_mip_level = base_mip_level;
+        self.range.level_count = level_count;
+        self
+    }
+
+    /// Restricts the barrier to `layer_count` array layers starting at `base_array_layer`.
+    pub fn array_layers(mut self, base_array_layer: u32, layer_count: u32) -> Self {
+        self.range.base_array_layer = base_array_layer;
+        self.range.layer_count = layer_count;
+        self
+    }
+
+    /// Transfers the ownership of the image from the `src` queue family to the `dst` queue
+    /// family.
+    ///
+    /// The same barrier, including the layout transition, must be recorded to a command buffer
+    /// of each queue family, first to release the image and then to acquire it.
+    pub fn queue_families(mut self, src: u32, dst: u32) -> Self {
+        self.src_queue_family = src;
+        self.dst_queue_family = dst;
+        self
+    }
+}
+
 /// Used to send instructions to the GPU.
+///
+/// In debug builds, the pipeline, vertex buffers and index buffer bound since the command buffer
+/// or the render pass began are tracked, and draws missing one of them return
+/// [`CommandError::MissingBinding`].
 pub struct CommandBuffer {
     device: Arc<Device>,
     cmd_buffer: vk::CommandBuffer,
+    // A Mutex rather than a Cell so the CommandBuffer is Sync in every build profile
+    #[cfg(debug_assertions)]
+    draw_state: std::sync::Mutex<DrawState>,
+}
+
+/// The bindings recorded to a CommandBuffer, checked before draws in debug builds.
+#[derive(Clone, Copy, Default)]
+#[cfg_attr(not(debug_assertions), allow(dead_code))]
+struct DrawState {
+    /// Mask of the vertex input bindings used by the bound pipeline, `None` if no pipeline is
+    /// bound.
+    pipeline_bindings: Option<u64>,
+    /// Mask of the vertex input bindings with a bound vertex buffer.
+    vertex_bindings: u64,
+    index_buffer: bool,
+}
+
+#[cfg_attr(not(debug_assertions), allow(dead_code))]
+impl DrawState {
+    /// Returns the first binding missing to record a draw.
+    fn missing(&self, indexed: bool) -> Option<DrawBinding> {
+        let pipeline_bindings = match self.pipeline_bindings {
+            Some(bindings) => bindings,
+            None => return Some(DrawBinding::Pipeline),
+        };
+
+        let unbound = pipeline_bindings & !self.vertex_bindings;
+        if unbound != 0 {
+            return Some(DrawBinding::VertexBuffer(unbound.trailing_zeros()))
+        }
+
+        match indexed && !self.index_buffer {
+            true => Some(DrawBinding::IndexBuffer),
+            false => None,
+        }
+    }
+}
+
+impl DebugObject for CommandBuffer {
+    fn debug_handle(&self) -> (ObjectType, u64) {
+        (ObjectType::COMMAND_BUFFER, vk::Handle::as_raw(self.cmd_buffer))
+    }
 }
 
 impl std::ops::Deref for CommandBuffer {
@@ -112,7 +406,8 @@ impl std::ops::Deref for CommandBuffer {
 impl CommandBuffer {
     /// Records instructions in the given closure to this command buffer.
     ///
-    /// Runs [`begin()`](Self::begin()), the closure then [`end()`](Self::end()).
+    /// Runs [`begin()`](Self::begin()), the closure then [`end()`](Self::end()). Returns the value
+    /// returned by the closure.
     ///
     /// # Examples
     ///
@@ -127,11 +422,59 @@ impl CommandBuffer {
     /// })?;
     /// # Ok::<(), Box<dyn std::error::Error>>(())
     /// ```
-    pub fn record<F: FnOnce()>(&self, flags: CommandBufferUsageFlags, f: F) -> Result<(), Error> {
+    pub fn record<R, F: FnOnce() -> R>(&self, flags: CommandBufferUsageFlags, f: F) -> Result<R, Error> {
         self.begin(flags)?;
-        f();
+        let result = f();
         self.end()?;
-        Ok(())
+        Ok(result)
+    }
+
+    /// Records instructions in the given closure to this secondary command buffer.
+    ///
+    /// The command buffer must be allocated with the [`SECONDARY`](CommandBufferLevel::SECONDARY)
+    /// level. It will be executed inside the render pass subpass described by `inheritance`, with
+    /// [`execute_commands()`](Self::execute_commands()) from a primary command buffer. Returns the
+    /// value returned by the closure.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # let event_loop = winit::event_loop::EventLoop::new();
+    /// # let window = winit::window::WindowBuilder::new().build(&event_loop)?;
+    /// # let device = plate::Device::new(&Default::default(), &Default::default(), Some(&window))?;
+    /// # let cmd_pool = plate::CommandPool::new(&device)?;
+    /// # let render_pass = plate::RenderPass::new(&device, &[], &[], &[])?;
+    /// let secondary = cmd_pool.alloc_cmd_buffer(plate::CommandBufferLevel::SECONDARY)?;
+    /// let inheritance = plate::RenderPassInheritance {
+    ///     render_pass: &render_pass,
+    ///     subpass: 0,
+    ///     framebuffer: None,
+    /// };
+    /// secondary.record_secondary(plate::CommandBufferUsageFlags::empty(), &inheritance, || {
+    ///     // secondary.draw(..);
+    /// })?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn record_secondary<R, F: FnOnce() -> R>(&self, flags: CommandBufferUsageFlags, inheritance: &RenderPassInheritance, f: F) -> Result<R, Error> {
+        let mut inheritance_info = vk::CommandBufferInheritanceInfo::builder()
+            .render_pass(inheritance.render_pass.render_pass)
+            .subpass(inheritance.subpass);
+        if let Some(framebuffer) = inheritance.framebuffer {
+            inheritance_info = inheritance_info.framebuffer(framebuffer.framebuffer);
+        }
+
+        let info = vk::CommandBufferBeginInfo::builder()
+            .flags(flags | CommandBufferUsageFlags::RENDER_PASS_CONTINUE)
+            .inheritance_info(&inheritance_info);
+        unsafe {
+            self.device.reset_command_buffer(self.cmd_buffer, vk::CommandBufferResetFlags::empty())?;
+            self.device.begin_command_buffer(self.cmd_buffer, &info)?;
+        }
+        self.reset_draw_state();
+
+        let result = f();
+        self.end()?;
+        Ok(result)
     }
 
     /// Begin recording instructions to this command buffer.
@@ -154,6 +497,7 @@ impl CommandBuffer {
             self.device.reset_command_buffer(self.cmd_buffer, vk::CommandBufferResetFlags::empty())?;
             self.device.begin_command_buffer(self.cmd_buffer, &info)?;
         }
+        self.reset_draw_state();
         Ok(())
     }
 
@@ -176,9 +520,40 @@ impl CommandBuffer {
         Ok(())
     }
 
-    /// Calls a [`draw`](ash::Device::cmd_draw()) command.
+    /// Executes secondary command buffers from this primary command buffer.
     ///
-    /// To be used when recording a CommandBuffer.
+    /// To be used when recording a CommandBuffer. When executed inside a render pass, it must have
+    /// been begun with [`SECONDARY_COMMAND_BUFFERS`](crate::SubpassContents::SECONDARY_COMMAND_BUFFERS)
+    /// contents, see [`RenderPass::begin_with_contents()`].
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # let event_loop = winit::event_loop::EventLoop::new();
+    /// # let window = winit::window::WindowBuilder::new().build(&event_loop)?;
+    /// # let device = plate::Device::new(&Default::default(), &Default::default(), Some(&window))?;
+    /// # let cmd_pool = plate::CommandPool::new(&device)?;
+    /// # let cmd_buffer = cmd_pool.alloc_cmd_buffer(plate::CommandBufferLevel::PRIMARY)?;
+    /// # let secondary = cmd_pool.alloc_cmd_buffer(plate::CommandBufferLevel::SECONDARY)?;
+    /// cmd_buffer.record(plate::CommandBufferUsageFlags::empty(), || {
+    ///     // render_pass.begin_with_contents(&cmd_buffer, .., plate::SubpassContents::SECONDARY_COMMAND_BUFFERS);
+    ///     cmd_buffer.execute_commands(&[&secondary]);
+    ///     // render_pass.end(&cmd_buffer);
+    /// })?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn execute_commands(&self, cmd_buffers: &[&CommandBuffer]) {
+        let cmd_buffers = cmd_buffers.iter()
+            .map(|cmd_buffer| cmd_buffer.cmd_buffer)
+            .collect::<Vec<_>>();
+        unsafe { self.device.cmd_execute_commands(self.cmd_buffer, &cmd_buffers) }
+    }
+
+    /// Inserts a pipeline barrier with buffer and image memory barriers.
+    ///
+    /// Operations of the `src_stage` stages recorded before the barrier complete before the
+    /// operations of the `dst_stage` stages recorded after it start. To be used when recording a
+    /// CommandBuffer, for example between a pass writing to a resource and a pass reading from it.
     ///
     /// # Examples
     ///
@@ -188,19 +563,331 @@ impl CommandBuffer {
     /// # let device = plate::Device::new(&Default::default(), &Default::default(), Some(&window))?;
     /// # let cmd_pool = plate::CommandPool::new(&device)?;
     /// # let cmd_buffer = cmd_pool.alloc_cmd_buffer(plate::CommandBufferLevel::PRIMARY)?;
+    /// # let image = plate::Image::new(&device, 0, 0,
+    /// # plate::Format::UNDEFINED, plate::ImageLayout::UNDEFINED,
+    /// # plate::ImageUsageFlags::empty(), plate::ImageAspectFlags::empty())?;
     /// cmd_buffer.record(plate::CommandBufferUsageFlags::empty(), || {
-    ///     cmd_buffer.draw(3, 1, 0, 0);
+    ///     // render to image..
+    ///     cmd_buffer.pipeline_barrier(
+    ///         plate::PipelineStage::COLOR_ATTACHMENT_OUTPUT,
+    ///         plate::PipelineStage::FRAGMENT_SHADER,
+    ///         &[],
+    ///         &[plate::ImageMemoryBarrier::new(&image)
+    ///             .src_access(plate::AccessFlags::COLOR_ATTACHMENT_WRITE)
+    ///             .dst_access(plate::AccessFlags::SHADER_READ)
+    ///             .layouts(plate::ImageLayout::COLOR_ATTACHMENT_OPTIMAL, plate::ImageLayout::SHADER_READ_ONLY_OPTIMAL)],
+    ///     );
+    ///     // sample image..
     /// })?;
     /// # Ok::<(), Box<dyn std::error::Error>>(())
     /// ```
-    pub fn draw(&self, vert_count: u32, instance_count: u32, first_vert: u32, first_instance: u32) {
-        unsafe { self.device.cmd_draw(self.cmd_buffer, vert_count, instance_count, first_vert, first_instance) }
+    pub fn pipeline_barrier(&self, src_stage: PipelineStage, dst_stage: PipelineStage, buffer_barriers: &[BufferMemoryBarrier], image_barriers: &[ImageMemoryBarrier]) {
+        let buffer_barriers = buffer_barriers.iter()
+            .map(|barrier| {
+                *vk::BufferMemoryBarrier::builder()
+                    .src_access_mask(barrier.src_access)
+                    .dst_access_mask(barrier.dst_access)
+                    .src_queue_family_index(barrier.src_queue_family)
+                    .dst_queue_family_index(barrier.dst_queue_family)
+                    .buffer(barrier.buffer)
+                    .offset(barrier.offset)
+                    .size(barrier.size)
+            })
+            .collect::<Vec<_>>();
+        let image_barriers = image_barriers.iter()
+            .map(|barrier| {
+                *vk::ImageMemoryBarrier::builder()
+                    .src_access_mask(barrier.src_access)
+                    .dst_access_mask(barrier.dst_access)
+                    .old_layout(barrier.old_layout)
+                    .new_layout(barrier.new_layout)
+                    .src_queue_family_index(barrier.src_queue_family)
+                    .dst_queue_family_index(barrier.dst_queue_family)
+                    .image(barrier.image)
+                    .subresource_range(barrier.range)
+            })
+            .collect::<Vec<_>>();
+
+        unsafe {
+            self.device.cmd_pipeline_barrier(
+                self.cmd_buffer,
+                src_stage,
+                dst_stage,
+                vk::DependencyFlags::empty(),
+                &[],
+                &buffer_barriers,
+                &image_barriers,
+            )
+        }
+    }
+
+    /// Calls a [`draw`](ash::Device::cmd_draw()) command.
+    ///
+    /// To be used when recording a CommandBuffer, after binding a
+    /// [`Pipeline`](crate::Pipeline) and the vertex buffers it uses. In debug builds, returns
+    /// [`CommandError::MissingBinding`] if one of them is not bound.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # let event_loop = winit::event_loop::EventLoop::new();
+    /// # let window = winit::window::WindowBuilder::new().build(&event_loop)?;
+    /// # let device = plate::Device::new(&Default::default(), &Default::default(), Some(&window))?;
+    /// # let cmd_pool = plate::CommandPool::new(&device)?;
+    /// # let cmd_buffer = cmd_pool.alloc_cmd_buffer(plate::CommandBufferLevel::PRIMARY)?;
+    /// # let render_pass = plate::RenderPass::new(&device, &[], &[], &[])?;
+    /// # let pipeline = plate::pipeline::Pipeline::new(&device, &render_pass, &[], &[],
+    /// # &Default::default())?;
+    /// cmd_buffer.record(plate::CommandBufferUsageFlags::empty(), || {
+    /// #   pipeline.bind(&cmd_buffer, (0, 0));
+    ///     cmd_buffer.draw(3, 1, 0, 0)
+    /// })??;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn draw(&self, vert_count: u32, instance_count: u32, first_vert: u32, first_instance: u32) -> Result<(), Error> {
+        self.check_draw_state(false)?;
+        unsafe { self.device.cmd_draw(self.cmd_buffer, vert_count, instance_count, first_vert, first_instance) };
+        Ok(())
     }
 
     /// Calls a [`draw_indexed`](ash::Device::cmd_draw_indexed()) command.
     ///
+    /// To be used when recording a CommandBuffer, after binding an index buffer, see
+    /// [`draw()`](Self::draw()).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # let event_loop = winit::event_loop::EventLoop::new();
+    /// # let window = winit::window::WindowBuilder::new().build(&event_loop)?;
+    /// # let device = plate::Device::new(&Default::default(), &Default::default(), Some(&window))?;
+    /// # let cmd_pool = plate::CommandPool::new(&device)?;
+    /// # let cmd_buffer = cmd_pool.alloc_cmd_buffer(plate::CommandBufferLevel::PRIMARY)?;
+    /// # let render_pass = plate::RenderPass::new(&device, &[], &[], &[])?;
+    /// # let pipeline = plate::pipeline::Pipeline::new(&device, &render_pass, &[], &[],
+    /// # &Default::default())?;
+    /// # let index_buffer = plate::IndexBuffer::new(&device, &[0, 1, 2], &cmd_pool)?;
+    /// cmd_buffer.record(plate::CommandBufferUsageFlags::empty(), || {
+    /// #   pipeline.bind(&cmd_buffer, (0, 0));
+    /// #   index_buffer.bind(&cmd_buffer);
+    ///     cmd_buffer.draw_indexed(3, 1, 0, 0, 0)
+    /// })??;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn draw_indexed(&self, index_count: u32, instance_count: u32, first_index: u32, vertex_offset: i32, first_instance: u32) -> Result<(), Error> {
+        self.check_draw_state(true)?;
+        unsafe { self.device.cmd_draw_indexed(self.cmd_buffer, index_count, instance_count, first_index, vertex_offset, first_instance) };
+        Ok(())
+    }
+
+    /// Calls a [`draw_indirect`](ash::Device::cmd_draw_indirect()) command.
+    ///
+    /// Reads `draw_count` [`DrawIndirectCommands`](crate::DrawIndirectCommand) from `buffer`,
+    /// starting `offset` bytes into it and `stride` bytes apart. The buffer must be created with
+    /// the [`INDIRECT_BUFFER`](crate::BufferUsageFlags::INDIRECT_BUFFER) usage. Without the
+    /// [`MULTI_DRAW_INDIRECT`](crate::DeviceFeatures::MULTI_DRAW_INDIRECT) feature only one command
+    /// is drawn, otherwise `draw_count` is clamped to the `maxDrawIndirectCount` device limit. To
+    /// be used when recording a CommandBuffer, the bindings are checked like
+    /// [`draw()`](Self::draw()).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # let event_loop = winit::event_loop::EventLoop::new();
+    /// # let window = winit::window::WindowBuilder::new().build(&event_loop)?;
+    /// # let device = plate::Device::new(&Default::default(), &Default::default(), Some(&window))?;
+    /// # let cmd_pool = plate::CommandPool::new(&device)?;
+    /// # let cmd_buffer = cmd_pool.alloc_cmd_buffer(plate::CommandBufferLevel::PRIMARY)?;
+    /// # let render_pass = plate::RenderPass::new(&device, &[], &[], &[])?;
+    /// # let pipeline = plate::pipeline::Pipeline::new(&device, &render_pass, &[], &[],
+    /// # &Default::default())?;
+    /// let commands = [plate::DrawIndirectCommand {
+    ///     vertex_count: 3,
+    ///     instance_count: 1,
+    ///     first_vertex: 0,
+    ///     first_instance: 0,
+    /// }];
+    /// let buffer = plate::Buffer::new_staged(&device, &commands, plate::BufferUsageFlags::INDIRECT_BUFFER, &cmd_pool)?;
+    /// let stride = std::mem::size_of::<plate::DrawIndirectCommand>() as u32;
+    /// cmd_buffer.record(plate::CommandBufferUsageFlags::empty(), || {
+    /// #   pipeline.bind(&cmd_buffer, (0, 0));
+    ///     cmd_buffer.draw_indirect(&buffer, 0, commands.len() as u32, stride)
+    /// })??;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn draw_indirect<T>(&self, buffer: &Buffer<T>, offset: u64, draw_count: u32, stride: u32) -> Result<(), Error> {
+        self.check_draw_state(false)?;
+        let draw_count = draw_count.min(self.device.max_draw_indirect_count);
+        unsafe { self.device.cmd_draw_indirect(self.cmd_buffer, buffer.buffer, offset, draw_count, stride) };
+        Ok(())
+    }
+
+    /// Calls a [`draw_indexed_indirect`](ash::Device::cmd_draw_indexed_indirect()) command.
+    ///
+    /// Reads `draw_count` [`DrawIndexedIndirectCommands`](crate::DrawIndexedIndirectCommand) from
+    /// `buffer`, starting `offset` bytes into it and `stride` bytes apart, see
+    /// [`draw_indirect()`](Self::draw_indirect()). To be used when recording a CommandBuffer,
+    /// after binding an index buffer.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # let event_loop = winit::event_loop::EventLoop::new();
+    /// # let window = winit::window::WindowBuilder::new().build(&event_loop)?;
+    /// # let device = plate::Device::new(&Default::default(), &Default::default(), Some(&window))?;
+    /// # let cmd_pool = plate::CommandPool::new(&device)?;
+    /// # let cmd_buffer = cmd_pool.alloc_cmd_buffer(plate::CommandBufferLevel::PRIMARY)?;
+    /// # let render_pass = plate::RenderPass::new(&device, &[], &[], &[])?;
+    /// # let pipeline = plate::pipeline::Pipeline::new(&device, &render_pass, &[], &[],
+    /// # &Default::default())?;
+    /// # let index_buffer = plate::IndexBuffer::new(&device, &[0, 1, 2], &cmd_pool)?;
+    /// let commands = [plate::DrawIndexedIndirectCommand {
+    ///     index_count: 6,
+    ///     instance_count: 1,
+    ///     first_index: 0,
+    ///     vertex_offset: 0,
+    ///     first_instance: 0,
+    /// }];
+    /// let buffer = plate::Buffer::new_staged(&device, &commands, plate::BufferUsageFlags::INDIRECT_BUFFER, &cmd_pool)?;
+    /// let stride = std::mem::size_of::<plate::DrawIndexedIndirectCommand>() as u32;
+    /// cmd_buffer.record(plate::CommandBufferUsageFlags::empty(), || {
+    /// #   pipeline.bind(&cmd_buffer, (0, 0));
+    /// #   index_buffer.bind(&cmd_buffer);
+    ///     cmd_buffer.draw_indexed_indirect(&buffer, 0, commands.len() as u32, stride)
+    /// })??;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn draw_indexed_indirect<T>(&self, buffer: &Buffer<T>, offset: u64, draw_count: u32, stride: u32) -> Result<(), Error> {
+        self.check_draw_state(true)?;
+        let draw_count = draw_count.min(self.device.max_draw_indirect_count);
+        unsafe { self.device.cmd_draw_indexed_indirect(self.cmd_buffer, buffer.buffer, offset, draw_count, stride) };
+        Ok(())
+    }
+
+    /// Records that a pipeline using the vertex input bindings in the `vertex_bindings` mask was
+    /// bound.
+    pub(crate) fn track_pipeline(&self, vertex_bindings: u64) {
+        self.update_draw_state(|state| state.pipeline_bindings = Some(vertex_bindings));
+    }
+
+    /// Records that a vertex buffer was bound at `binding`.
+    pub(crate) fn track_vertex_buffer(&self, binding: u32) {
+        self.update_draw_state(|state| state.vertex_bindings |= 1u64.checked_shl(binding).unwrap_or(0));
+    }
+
+    /// Records that an index buffer was bound.
+    pub(crate) fn track_index_buffer(&self) {
+        self.update_draw_state(|state| state.index_buffer = true);
+    }
+
+    /// Forgets the tracked bindings, at the start of a command buffer or render pass.
+    pub(crate) fn reset_draw_state(&self) {
+        self.update_draw_state(|state| *state = DrawState::default());
+    }
+
+    #[cfg(debug_assertions)]
+    fn update_draw_state<F: FnOnce(&mut DrawState)>(&self, f: F) {
+        f(&mut self.draw_state.lock().unwrap_or_else(|e| e.into_inner()));
+    }
+
+    #[cfg(not(debug_assertions))]
+    fn update_draw_state<F: FnOnce(&mut DrawState)>(&self, _: F) {}
+
+    #[cfg(debug_assertions)]
+    fn check_draw_state(&self, indexed: bool) -> Result<(), CommandError> {
+        match self.draw_state.lock().unwrap_or_else(|e| e.into_inner()).missing(indexed) {
+            Some(binding) => Err(CommandError::MissingBinding(binding)),
+            None => Ok(()),
+        }
+    }
+
+    #[cfg(not(debug_assertions))]
+    fn check_draw_state(&self, _: bool) -> Result<(), CommandError> {
+        Ok(())
+    }
+
+    /// Sets the viewport for the following draw commands.
+    ///
+    /// To be used when recording a CommandBuffer, after binding a [`Pipeline`](crate::Pipeline)
+    /// created with `dynamic_viewport`.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # let event_loop = winit::event_loop::EventLoop::new();
+    /// # let window = winit::window::WindowBuilder::new().build(&event_loop)?;
+    /// # let device = plate::Device::new(&Default::default(), &Default::default(), Some(&window))?;
+    /// # let cmd_pool = plate::CommandPool::new(&device)?;
+    /// # let cmd_buffer = cmd_pool.alloc_cmd_buffer(plate::CommandBufferLevel::PRIMARY)?;
+    /// cmd_buffer.record(plate::CommandBufferUsageFlags::empty(), || {
+    ///     // Render to the left half of a 800x600 target
+    ///     cmd_buffer.set_viewport(0.0, 0.0, 400.0, 600.0, 0.0, 1.0);
+    /// })?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn set_viewport(&self, x: f32, y: f32, width: f32, height: f32, min_depth: f32, max_depth: f32) {
+        let viewports = [vk::Viewport { x, y, width, height, min_depth, max_depth }];
+        unsafe { self.device.cmd_set_viewport(self.cmd_buffer, 0, &viewports) }
+    }
+
+    /// Sets the scissor rectangle for the following draw commands.
+    ///
+    /// To be used when recording a CommandBuffer, after binding a [`Pipeline`](crate::Pipeline)
+    /// created with `dynamic_viewport`. Fragments outside of the rectangle are discarded.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # let event_loop = winit::event_loop::EventLoop::new();
+    /// # let window = winit::window::WindowBuilder::new().build(&event_loop)?;
+    /// # let device = plate::Device::new(&Default::default(), &Default::default(), Some(&window))?;
+    /// # let cmd_pool = plate::CommandPool::new(&device)?;
+    /// # let cmd_buffer = cmd_pool.alloc_cmd_buffer(plate::CommandBufferLevel::PRIMARY)?;
+    /// cmd_buffer.record(plate::CommandBufferUsageFlags::empty(), || {
+    ///     cmd_buffer.set_scissor(0, 0, 400, 600);
+    /// })?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn set_scissor(&self, x: i32, y: i32, width: u32, height: u32) {
+        let scissors = [vk::Rect2D {
+            offset: vk::Offset2D { x, y },
+            extent: vk::Extent2D { width, height },
+        }];
+        unsafe { self.device.cmd_set_scissor(self.cmd_buffer, 0, &scissors) }
+    }
+
+    /// Resets every timestamp of a [`QueryPool`].
+    ///
+    /// To be used when recording a CommandBuffer, outside of a render pass. The QueryPool must be
+    /// reset before its timestamps are first written and before writing them again.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # let event_loop = winit::event_loop::EventLoop::new();
+    /// # let window = winit::window::WindowBuilder::new().build(&event_loop)?;
+    /// # let device = plate::Device::new(&Default::default(), &Default::default(), Some(&window))?;
+    /// # let cmd_pool = plate::CommandPool::new(&device)?;
+    /// # let cmd_buffer = cmd_pool.alloc_cmd_buffer(plate::CommandBufferLevel::PRIMARY)?;
+    /// # let query_pool = plate::QueryPool::new(&device, 2)?;
+    /// cmd_buffer.record(plate::CommandBufferUsageFlags::empty(), || {
+    ///     cmd_buffer.reset_query_pool(&query_pool);
+    /// })?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn reset_query_pool(&self, query_pool: &QueryPool) {
+        unsafe { self.device.cmd_reset_query_pool(self.cmd_buffer, query_pool.pool, 0, query_pool.count()) }
+    }
+
+    /// Writes the timestamp at `index` of a [`QueryPool`] once all previous commands reach
+    /// `stage`.
+    ///
     /// To be used when recording a CommandBuffer.
     ///
+    /// # Panics
+    ///
+    /// Panics if `index` is not less than the [`count()`](QueryPool::count()) of the QueryPool.
+    ///
     /// # Examples
     ///
     /// ```no_run
@@ -209,12 +896,94 @@ impl CommandBuffer {
     /// # let device = plate::Device::new(&Default::default(), &Default::default(), Some(&window))?;
     /// # let cmd_pool = plate::CommandPool::new(&device)?;
     /// # let cmd_buffer = cmd_pool.alloc_cmd_buffer(plate::CommandBufferLevel::PRIMARY)?;
+    /// # let query_pool = plate::QueryPool::new(&device, 2)?;
     /// cmd_buffer.record(plate::CommandBufferUsageFlags::empty(), || {
-    ///     cmd_buffer.draw_indexed(3, 1, 0, 0, 0);
+    ///     cmd_buffer.reset_query_pool(&query_pool);
+    ///     cmd_buffer.write_timestamp(&query_pool, plate::PipelineStage::TOP_OF_PIPE, 0);
+    ///     // cmd_buffer.draw(..);
+    ///     cmd_buffer.write_timestamp(&query_pool, plate::PipelineStage::BOTTOM_OF_PIPE, 1);
     /// })?;
     /// # Ok::<(), Box<dyn std::error::Error>>(())
     /// ```
-    pub fn draw_indexed(&self, index_count: u32, instance_count: u32, first_index: u32, vertex_offset: i32, first_instance: u32) {
-        unsafe { self.device.cmd_draw_indexed(self.cmd_buffer, index_count, instance_count, first_index, vertex_offset, first_instance) }
+    pub fn write_timestamp(&self, query_pool: &QueryPool, stage: PipelineStage, index: u32) {
+        assert!(index < query_pool.count());
+        unsafe { self.device.cmd_write_timestamp(self.cmd_buffer, stage, query_pool.pool, index) }
+    }
+
+    /// Opens a labeled region of commands, shown in graphics debuggers such as RenderDoc.
+    ///
+    /// To be used when recording a CommandBuffer, the region is closed with
+    /// [`end_debug_label()`](Self::end_debug_label()). Regions can be nested. Does nothing if the
+    /// Device was created without validation layers.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # let event_loop = winit::event_loop::EventLoop::new();
+    /// # let window = winit::window::WindowBuilder::new().build(&event_loop)?;
+    /// # let device = plate::Device::new(&Default::default(), &Default::default(), Some(&window))?;
+    /// # let cmd_pool = plate::CommandPool::new(&device)?;
+    /// # let cmd_buffer = cmd_pool.alloc_cmd_buffer(plate::CommandBufferLevel::PRIMARY)?;
+    /// cmd_buffer.record(plate::CommandBufferUsageFlags::empty(), || {
+    ///     cmd_buffer.begin_debug_label("shadow pass", [1.0, 0.5, 0.0, 1.0]);
+    ///     // ..
+    ///     cmd_buffer.end_debug_label();
+    /// })?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn begin_debug_label(&self, name: &str, color: [f32; 4]) {
+        if let Some(debugger) = self.device.instance.debugger() {
+            debugger.begin_label(self.cmd_buffer, name, color);
+        }
+    }
+
+    /// Closes the region opened by the last [`begin_debug_label()`](Self::begin_debug_label()).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # let event_loop = winit::event_loop::EventLoop::new();
+    /// # let window = winit::window::WindowBuilder::new().build(&event_loop)?;
+    /// # let device = plate::Device::new(&Default::default(), &Default::default(), Some(&window))?;
+    /// # let cmd_pool = plate::CommandPool::new(&device)?;
+    /// # let cmd_buffer = cmd_pool.alloc_cmd_buffer(plate::CommandBufferLevel::PRIMARY)?;
+    /// cmd_buffer.record(plate::CommandBufferUsageFlags::empty(), || {
+    ///     cmd_buffer.begin_debug_label("shadow pass", [1.0, 0.5, 0.0, 1.0]);
+    ///     // ..
+    ///     cmd_buffer.end_debug_label();
+    /// })?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn end_debug_label(&self) {
+        if let Some(debugger) = self.device.instance.debugger() {
+            debugger.end_label(self.cmd_buffer);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_draw_state_missing() {
+        let mut state = DrawState::default();
+        assert_eq!(state.missing(false), Some(DrawBinding::Pipeline));
+
+        state.pipeline_bindings = Some(0b11);
+        state.vertex_bindings = 0b01;
+        assert_eq!(state.missing(false), Some(DrawBinding::VertexBuffer(1)));
+
+        state.vertex_bindings = 0b111;
+        assert_eq!(state.missing(false), None);
+        assert_eq!(state.missing(true), Some(DrawBinding::IndexBuffer));
+
+        state.index_buffer = true;
+        assert_eq!(state.missing(true), None);
+    }
+    #[test]
+    fn test_command_buffer_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<CommandBuffer>();
     }
 }
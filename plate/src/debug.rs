@@ -32,6 +32,34 @@ impl Debugger {
             debug_messenger,
         })
     }
+
+    pub fn set_object_name(&self, device: vk::Device, object_type: vk::ObjectType, handle: u64, name: &str) -> Result<(), Error> {
+        let name = c_string(name);
+        let info = vk::DebugUtilsObjectNameInfoEXT::builder()
+            .object_type(object_type)
+            .object_handle(handle)
+            .object_name(&name);
+        unsafe { self.debug_utils.set_debug_utils_object_name(device, &info)? };
+        Ok(())
+    }
+
+    pub fn begin_label(&self, cmd_buffer: vk::CommandBuffer, name: &str, color: [f32; 4]) {
+        let name = c_string(name);
+        let label = vk::DebugUtilsLabelEXT::builder()
+            .label_name(&name)
+            .color(color);
+        unsafe { self.debug_utils.cmd_begin_debug_utils_label(cmd_buffer, &label) }
+    }
+
+    pub fn end_label(&self, cmd_buffer: vk::CommandBuffer) {
+        unsafe { self.debug_utils.cmd_end_debug_utils_label(cmd_buffer) }
+    }
+}
+
+/// Converts a name to a CString, truncating it at the first nul byte.
+fn c_string(name: &str) -> ffi::CString {
+    let name = name.split('\0').next().unwrap_or_default();
+    ffi::CString::new(name).expect("Should never fail after truncating at the first nul byte")
 }
 
 pub fn debug_messenger_info() -> vk::DebugUtilsMessengerCreateInfoEXT {
@@ -6,6 +6,8 @@ use crate::{image::*, Buffer, CommandBuffer, Device, Error, Pipeline, ImageLayou
 
 pub use vk::DescriptorType;
 pub use vk::ShaderStageFlags as ShaderStage;
+pub use vk::DescriptorBindingFlags;
+pub use vk::DescriptorPoolCreateFlags as DescriptorPoolFlags;
 
 /// Errors from the descriptor module.
 #[derive(thiserror::Error, Debug)]
@@ -16,6 +18,37 @@ pub enum DescriptorError {
         actual: usize,
         expected: usize,
     },
+    /// The [`DescriptorPool`] does not have enough capacity left for the requested descriptor sets.
+    #[error("The descriptor pool is out of memory")]
+    OutOfPoolMemory,
+    /// [`DescriptorBindingFlags`] were used but the [`Device`] does not support descriptor
+    /// indexing, see [`Device::supports_descriptor_indexing()`].
+    #[error("Descriptor binding flags require descriptor indexing, which the device does not support")]
+    DescriptorIndexingUnsupported,
+    /// The [`DescriptorSet`] layout has no binding with the given index.
+    #[error("The descriptor set has no binding {0}")]
+    BindingNotFound(u32),
+    /// The array element is out of the bounds of the binding.
+    #[error("Array element {index} is out of bounds, the binding has {count} descriptors")]
+    ArrayIndexOutOfBounds {
+        index: u32,
+        count: u32,
+    },
+    /// The dynamic offset of the binding in bytes is not a multiple of the
+    /// `minUniformBufferOffsetAlignment` or `minStorageBufferOffsetAlignment` device limit.
+    #[error("Dynamic offset of {offset} bytes for binding {binding} is not a multiple of {alignment} bytes")]
+    MisalignedDynamicOffset {
+        binding: u32,
+        offset: u64,
+        alignment: u64,
+    },
+    /// The range of the binding moved by the dynamic offset exceeds the size of the buffer.
+    #[error("Dynamic offset of {offset} bytes for binding {binding} moves its range past the end of the buffer of {size} bytes")]
+    DynamicOffsetOutOfRange {
+        binding: u32,
+        offset: u64,
+        size: u64,
+    },
 }
 
 /// A Component for building a descriptor pool.
@@ -48,6 +81,7 @@ pub struct PoolSize {
 pub struct DescriptorPoolBuilder {
     sizes: Vec<PoolSize>,
     max_sets: Option<u32>,
+    flags: DescriptorPoolFlags,
 }
 
 impl Default for DescriptorPoolBuilder {
@@ -55,6 +89,7 @@ impl Default for DescriptorPoolBuilder {
         Self {
             sizes: vec![],
             max_sets: None,
+            flags: DescriptorPoolFlags::empty(),
         }
     }
 }
@@ -72,6 +107,13 @@ impl DescriptorPoolBuilder {
         self
     }
 
+    /// Set the flags of the DescriptorPool, such as
+    /// [`UPDATE_AFTER_BIND`](DescriptorPoolFlags::UPDATE_AFTER_BIND).
+    pub fn flags(&mut self, flags: DescriptorPoolFlags) -> &mut Self {
+        self.flags = flags;
+        self
+    }
+
     /// Builds A DescriptorPool from this builder.
     ///
     /// If the value of `max_sets` is not set, it will default to the sum of all available sets
@@ -80,7 +122,7 @@ impl DescriptorPoolBuilder {
         let max_sets = self
             .max_sets
             .unwrap_or(self.sizes.iter().map(|size| size.count).sum());
-        DescriptorPool::new(device, &self.sizes, max_sets)
+        DescriptorPool::with_flags(device, &self.sizes, max_sets, self.flags)
     }
 }
 
@@ -122,6 +164,33 @@ impl DescriptorPool {
     /// # Ok::<(), Box<dyn std::error::Error>>(())
     /// ```
     pub fn new(device: &Arc<Device>, sizes: &[PoolSize], max_sets: u32) -> Result<Self, Error> {
+        Self::with_flags(device, sizes, max_sets, DescriptorPoolFlags::empty())
+    }
+
+    /// Creates a DescriptorPool with [`DescriptorPoolFlags`].
+    ///
+    /// Sets with [`UPDATE_AFTER_BIND`](DescriptorBindingFlags::UPDATE_AFTER_BIND) bindings must
+    /// be allocated from a pool created with
+    /// [`UPDATE_AFTER_BIND`](DescriptorPoolFlags::UPDATE_AFTER_BIND).
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # let event_loop = winit::event_loop::EventLoop::new();
+    /// # let window = winit::window::WindowBuilder::new().build(&event_loop)?;
+    /// # let device = plate::Device::new(&Default::default(), &Default::default(), Some(&window))?;
+    /// let descriptor_pool = plate::DescriptorPool::with_flags(
+    ///     &device,
+    ///     &[plate::PoolSize {
+    ///         ty: plate::DescriptorType::COMBINED_IMAGE_SAMPLER,
+    ///         count: 1024,
+    ///     }],
+    ///     1,
+    ///     plate::DescriptorPoolFlags::UPDATE_AFTER_BIND,
+    /// )?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn with_flags(device: &Arc<Device>, sizes: &[PoolSize], max_sets: u32, flags: DescriptorPoolFlags) -> Result<Self, Error> {
         let pool_sizes = sizes
             .iter()
             .map(|size| {
@@ -132,6 +201,7 @@ impl DescriptorPool {
             .collect::<Vec<_>>();
 
         let pool_info = vk::DescriptorPoolCreateInfo::builder()
+            .flags(flags)
             .pool_sizes(&pool_sizes)
             .max_sets(max_sets);
 
@@ -143,6 +213,31 @@ impl DescriptorPool {
         })
     }
 
+    /// Resets the DescriptorPool, returning all the [`DescriptorSets`](DescriptorSet) allocated from
+    /// it to the pool.
+    ///
+    /// The DescriptorSets previously allocated from the pool must not be used after the reset.
+    /// Useful to reuse a pool for descriptor sets that only live for a single frame.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # let event_loop = winit::event_loop::EventLoop::new();
+    /// # let window = winit::window::WindowBuilder::new().build(&event_loop)?;
+    /// # let device = plate::Device::new(&Default::default(), &Default::default(), Some(&window))?;
+    /// # let layout = plate::DescriptorSetLayout::new(&device, &[])?;
+    /// let pool = plate::DescriptorPool::new(&device, &[], 1)?;
+    /// let descriptor_set = plate::DescriptorAllocator::new(&device).allocate(&layout, &pool)?;
+    /// // ..
+    /// drop(descriptor_set);
+    /// pool.reset()?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn reset(&self) -> Result<(), Error> {
+        unsafe { self.device.reset_descriptor_pool(self.pool, vk::DescriptorPoolResetFlags::empty())? };
+        Ok(())
+    }
+
     /// Returns a [`DescriptorPoolBuilder`] if you prefer to use the builder pattern.
     pub fn builder() -> DescriptorPoolBuilder {
         DescriptorPoolBuilder::default()
@@ -160,12 +255,43 @@ pub struct LayoutBinding {
     /// The ammount of descriptors to allocate. Must not exceed the maximum amount of that type
     /// described in the [`DescriptorPool`].
     pub count: u32,
+    /// Descriptor indexing flags of the binding.
+    ///
+    /// [`PARTIALLY_BOUND`](DescriptorBindingFlags::PARTIALLY_BOUND) allows array elements to be
+    /// left unwritten, [`UPDATE_AFTER_BIND`](DescriptorBindingFlags::UPDATE_AFTER_BIND) allows
+    /// them to be [`updated`](DescriptorSet::update_image_at()) while the set is bound and
+    /// [`VARIABLE_DESCRIPTOR_COUNT`](DescriptorBindingFlags::VARIABLE_DESCRIPTOR_COUNT), only
+    /// valid for the last binding, makes `count` an upper bound with the actual count set on
+    /// [`allocation`](DescriptorAllocator::variable_descriptor_count()). Require descriptor
+    /// indexing, see [`Device::supports_descriptor_indexing()`].
+    pub flags: DescriptorBindingFlags,
+}
+
+impl Default for LayoutBinding {
+    fn default() -> Self {
+        Self {
+            binding: 0,
+            ty: DescriptorType::UNIFORM_BUFFER,
+            stage: ShaderStage::empty(),
+            count: 1,
+            flags: DescriptorBindingFlags::empty(),
+        }
+    }
 }
 
 /// A DescriptorSetLayout indicates what descriptor types will be allocated from a [`DescriptorPool`].
 pub struct DescriptorSetLayout {
     device: Arc<Device>,
     pub(crate) layout: vk::DescriptorSetLayout,
+    bindings: Vec<BindingInfo>,
+}
+
+#[derive(Clone, Copy)]
+struct BindingInfo {
+    binding: u32,
+    ty: DescriptorType,
+    count: u32,
+    flags: DescriptorBindingFlags,
 }
 
 impl Drop for DescriptorSetLayout {
@@ -193,11 +319,23 @@ impl DescriptorSetLayout {
     ///         ty: plate::DescriptorType::UNIFORM_BUFFER,
     ///         stage: plate::ShaderStage::VERTEX,
     ///         count: 1,
+    ///         ..Default::default()
     ///     }],
     /// )?;
     /// # Ok::<(), Box<dyn std::error::Error>>(())
     /// ```
     pub fn new(device: &Arc<Device>, bindings: &[LayoutBinding]) -> Result<Self, Error> {
+        let binding_infos = bindings.iter()
+            .map(|b| BindingInfo { binding: b.binding, ty: b.ty, count: b.count, flags: b.flags })
+            .collect::<Vec<_>>();
+        let binding_flags = bindings.iter()
+            .map(|b| b.flags)
+            .collect::<Vec<_>>();
+        let indexed = binding_flags.iter().any(|f| !f.is_empty());
+        if indexed && !device.descriptor_indexing {
+            return Err(DescriptorError::DescriptorIndexingUnsupported.into())
+        }
+
         let bindings = bindings
             .iter()
             .map(|binding| {
@@ -209,13 +347,22 @@ impl DescriptorSetLayout {
             })
             .collect::<Vec<_>>();
 
-        let layout_info = vk::DescriptorSetLayoutCreateInfo::builder().bindings(&bindings);
+        let mut flags_info = vk::DescriptorSetLayoutBindingFlagsCreateInfo::builder()
+            .binding_flags(&binding_flags);
+        let mut layout_info = vk::DescriptorSetLayoutCreateInfo::builder().bindings(&bindings);
+        if indexed {
+            layout_info = layout_info.push_next(&mut flags_info);
+        }
+        if binding_flags.iter().any(|f| f.contains(DescriptorBindingFlags::UPDATE_AFTER_BIND)) {
+            layout_info = layout_info.flags(vk::DescriptorSetLayoutCreateFlags::UPDATE_AFTER_BIND_POOL);
+        }
 
         let layout = unsafe { device.create_descriptor_set_layout(&layout_info, None)? };
 
         Ok(Self {
             device: Arc::clone(&device),
             layout,
+            bindings: binding_infos,
         })
     }
 }
@@ -226,6 +373,7 @@ enum WriteDescriptor {
         ty: DescriptorType,
         info: [vk::DescriptorBufferInfo; 1],
         alignment: usize,
+        size: u64,
     },
     Image {
         binding: u32,
@@ -238,6 +386,7 @@ enum WriteDescriptor {
 pub struct DescriptorAllocator {
     device: Arc<Device>,
     writes: Vec<WriteDescriptor>,
+    variable_count: Option<u32>,
 }
 
 impl DescriptorAllocator {
@@ -256,9 +405,35 @@ impl DescriptorAllocator {
         Self {
             device: Arc::clone(&device),
             writes: vec![],
+            variable_count: None,
         }
     }
 
+    /// Sets the number of descriptors of the
+    /// [`VARIABLE_DESCRIPTOR_COUNT`](DescriptorBindingFlags::VARIABLE_DESCRIPTOR_COUNT) binding
+    /// in the allocated sets.
+    ///
+    /// Must not exceed the `count` of the [`LayoutBinding`]. If not set, the variable binding has
+    /// no descriptors.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # let event_loop = winit::event_loop::EventLoop::new();
+    /// # let window = winit::window::WindowBuilder::new().build(&event_loop)?;
+    /// # let device = plate::Device::new(&Default::default(), &Default::default(), Some(&window))?;
+    /// # let layout = plate::DescriptorSetLayout::new(&device, &[])?;
+    /// # let pool = plate::DescriptorPool::new(&device, &[], 1)?;
+    /// let descriptor_set = plate::DescriptorAllocator::new(&device)
+    ///     .variable_descriptor_count(256)
+    ///     .allocate(&layout, &pool)?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn variable_descriptor_count(&mut self, count: u32) -> &mut Self {
+        self.variable_count = Some(count);
+        self
+    }
+
     /// Binds a [`Buffer`] to a descriptor binding.
     ///
     /// # Examples
@@ -272,7 +447,7 @@ impl DescriptorAllocator {
     ///     # 1,
     ///     # plate::BufferUsageFlags::UNIFORM_BUFFER,
     ///     # plate::SharingMode::EXCLUSIVE,
-    ///     # plate::MemoryPropertyFlags::HOST_VISIBLE | plate::MemoryPropertyFlags::HOST_VISIBLE,
+    ///     # plate::MemoryPropertyFlags::HOST_VISIBLE | plate::MemoryPropertyFlags::HOST_COHERENT,
     /// # )?;
     /// let allocator = plate::DescriptorAllocator::new(&device)
     ///     .add_buffer_binding(0, plate::DescriptorType::UNIFORM_BUFFER, &buffer);
@@ -303,7 +478,7 @@ impl DescriptorAllocator {
     ///     # 1,
     ///     # plate::BufferUsageFlags::UNIFORM_BUFFER,
     ///     # plate::SharingMode::EXCLUSIVE,
-    ///     # plate::MemoryPropertyFlags::HOST_VISIBLE | plate::MemoryPropertyFlags::HOST_VISIBLE,
+    ///     # plate::MemoryPropertyFlags::HOST_VISIBLE | plate::MemoryPropertyFlags::HOST_COHERENT,
     /// # )?;
     /// let allocator = plate::DescriptorAllocator::new(&device)
     ///     .add_buffer_index_binding(0, 0, 1, plate::DescriptorType::UNIFORM_BUFFER, &buffer);
@@ -323,11 +498,42 @@ impl DescriptorAllocator {
             ty,
             info,
             alignment: buffer.alignment_size,
+            size: (buffer.alignment_size * buffer.instance_count) as u64,
         };
         self.writes.push(write);
         self
     }
 
+    /// Binds the first `instance_count` instances of a [`Buffer`] to a dynamic descriptor
+    /// binding.
+    ///
+    /// `ty` must be [`UNIFORM_BUFFER_DYNAMIC`](DescriptorType::UNIFORM_BUFFER_DYNAMIC) or
+    /// [`STORAGE_BUFFER_DYNAMIC`](DescriptorType::STORAGE_BUFFER_DYNAMIC). The bound range is
+    /// moved through the buffer by the dynamic offsets passed to [`DescriptorSet::bind()`], in
+    /// instances, such as the regions of a [`PerFrameBuffer`](crate::PerFrameBuffer).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # let event_loop = winit::event_loop::EventLoop::new();
+    /// # let window = winit::window::WindowBuilder::new().build(&event_loop)?;
+    /// # let device = plate::Device::new(&Default::default(), &Default::default(), Some(&window))?;
+    /// # let ubo: plate::PerFrameBuffer<u32> = plate::PerFrameBuffer::new(&device, 1, 2,
+    /// # plate::BufferUsageFlags::UNIFORM_BUFFER)?;
+    /// let allocator = plate::DescriptorAllocator::new(&device)
+    ///     .add_dynamic_buffer_binding(0, 1, plate::DescriptorType::UNIFORM_BUFFER_DYNAMIC, ubo.buffer());
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn add_dynamic_buffer_binding<T>(
+        &mut self,
+        binding: u32,
+        instance_count: usize,
+        ty: DescriptorType,
+        buffer: &Buffer<T>,
+    ) -> &mut Self {
+        self.add_buffer_index_binding(binding, 0, instance_count, ty, buffer)
+    }
+
     /// Binds a [`Image`] to a descriptor binding.
     ///
     /// # Examples
@@ -366,6 +572,45 @@ impl DescriptorAllocator {
         self
     }
 
+    /// Binds a [`ImageView`] to a descriptor binding.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # let event_loop = winit::event_loop::EventLoop::new();
+    /// # let window = winit::window::WindowBuilder::new().build(&event_loop)?;
+    /// # let device = plate::Device::new(&Default::default(), &Default::default(), Some(&window))?;
+    /// # let image = plate::Image::new(&device, 0, 0,
+    /// # plate::Format::UNDEFINED, plate::ImageLayout::UNDEFINED,
+    /// # plate::ImageUsageFlags::empty(), plate::ImageAspectFlags::empty())?;
+    /// # let view = plate::ImageView::new(&device, &image, plate::Format::UNDEFINED)?;
+    /// # let sampler = plate::Sampler::new(&device, &Default::default())?;
+    /// let allocator = plate::DescriptorAllocator::new(&device)
+    ///     .add_image_view_binding(
+    ///         0, plate::DescriptorType::COMBINED_IMAGE_SAMPLER,
+    ///         &view, &sampler,
+    ///         plate::ImageLayout::SHADER_READ_ONLY_OPTIMAL
+    ///     );
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn add_image_view_binding(
+        &mut self,
+        binding: u32,
+        ty: DescriptorType,
+        view: &ImageView,
+        sampler: &Sampler,
+        layout: ImageLayout,
+    ) -> &mut Self {
+        let info = [view.descriptor_info(sampler, layout)];
+        let write = WriteDescriptor::Image {
+            binding,
+            ty,
+            info,
+        };
+        self.writes.push(write);
+        self
+    }
+
     /// Allocates a [`DescriptorSet`] with the added bindings.
     ///
     /// The type and number of the bindings must match the provided DescriptorSetLayout and the
@@ -382,7 +627,7 @@ impl DescriptorAllocator {
     ///     # 1,
     ///     # plate::BufferUsageFlags::UNIFORM_BUFFER,
     ///     # plate::SharingMode::EXCLUSIVE,
-    ///     # plate::MemoryPropertyFlags::HOST_VISIBLE | plate::MemoryPropertyFlags::HOST_VISIBLE,
+    ///     # plate::MemoryPropertyFlags::HOST_VISIBLE | plate::MemoryPropertyFlags::HOST_COHERENT,
     /// # )?;
     /// # let layout = plate::DescriptorSetLayout::new(&device, &[])?;
     /// # let pool = plate::DescriptorPool::new(&device, &[], 2)?;
@@ -397,48 +642,116 @@ impl DescriptorAllocator {
         layout: &DescriptorSetLayout,
         pool: &DescriptorPool,
     ) -> Result<DescriptorSet, Error> {
-        let layouts = [layout.layout];
-        let alloc_info = vk::DescriptorSetAllocateInfo::builder()
+        let mut sets = self.allocate_many(1, layout, pool)?;
+        Ok(sets.remove(0))
+    }
+
+    /// Allocates `count` [`DescriptorSets`](DescriptorSet) with the added bindings.
+    ///
+    /// All the sets are allocated with a single call and share the same bindings. Returns
+    /// [`DescriptorError::OutOfPoolMemory`] if the DescriptorPool does not have enough capacity
+    /// left, in which case none of the sets are allocated.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # let event_loop = winit::event_loop::EventLoop::new();
+    /// # let window = winit::window::WindowBuilder::new().build(&event_loop)?;
+    /// # let device = plate::Device::new(&Default::default(), &Default::default(), Some(&window))?;
+    /// # let layout = plate::DescriptorSetLayout::new(&device, &[])?;
+    /// # let pool = plate::DescriptorPool::new(&device, &[], 4)?;
+    /// let descriptor_sets = plate::DescriptorAllocator::new(&device)
+    ///     .allocate_many(4, &layout, &pool)?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn allocate_many(
+        &mut self,
+        count: usize,
+        layout: &DescriptorSetLayout,
+        pool: &DescriptorPool,
+    ) -> Result<Vec<DescriptorSet>, Error> {
+        let layouts = vec![layout.layout; count];
+        let variable_counts = vec![self.variable_count.unwrap_or(0); count];
+        let mut variable_count_info = vk::DescriptorSetVariableDescriptorCountAllocateInfo::builder()
+            .descriptor_counts(&variable_counts);
+        let mut alloc_info = vk::DescriptorSetAllocateInfo::builder()
             .descriptor_pool(pool.pool)
             .set_layouts(&layouts);
+        if self.variable_count.is_some() {
+            alloc_info = alloc_info.push_next(&mut variable_count_info);
+        }
 
-        let set = unsafe { self.device.allocate_descriptor_sets(&alloc_info)?[0] };
+        let bindings = layout.bindings.iter()
+            .map(|b| match (b.flags.contains(DescriptorBindingFlags::VARIABLE_DESCRIPTOR_COUNT), self.variable_count) {
+                (true, count) => BindingInfo { count: count.unwrap_or(0), ..*b },
+                (false, _) => *b,
+            })
+            .collect::<Vec<_>>();
 
-        let mut dynamic_sizes = vec![];
+        let sets = match unsafe { self.device.allocate_descriptor_sets(&alloc_info) } {
+            Ok(sets) => sets,
+            Err(vk::Result::ERROR_OUT_OF_POOL_MEMORY | vk::Result::ERROR_FRAGMENTED_POOL) => {
+                return Err(DescriptorError::OutOfPoolMemory.into())
+            }
+            Err(e) => return Err(e.into()),
+        };
 
-        let writes = self
-            .writes
-            .iter_mut()
-            .map(|write| match write {
-                WriteDescriptor::Buffer { binding, ty, info, alignment } => {
-                    if (*ty == DescriptorType::UNIFORM_BUFFER_DYNAMIC) || (*ty == DescriptorType::STORAGE_BUFFER_DYNAMIC) {
-                        dynamic_sizes.push(*alignment as u32);
-                    }
-                    *vk::WriteDescriptorSet::builder()
-                        .dst_set(set)
-                        .dst_binding(*binding)
-                        .descriptor_type(*ty)
-                        .dst_array_element(0)
-                        .buffer_info(info)
-                }
-                WriteDescriptor::Image { binding, ty, info } => {
-                    *vk::WriteDescriptorSet::builder()
-                        .dst_set(set)
-                        .dst_binding(*binding)
-                        .descriptor_type(*ty)
-                        .dst_array_element(0)
-                        .image_info(info)
+        let limits = unsafe { self.device.instance.get_physical_device_properties(self.device.physical_device).limits };
+        let mut dynamic_buffers = self.writes.iter()
+            .filter_map(|write| match write {
+                WriteDescriptor::Buffer { binding, ty, info, alignment, size } => {
+                    let min_alignment = match *ty {
+                        DescriptorType::UNIFORM_BUFFER_DYNAMIC => limits.min_uniform_buffer_offset_alignment,
+                        DescriptorType::STORAGE_BUFFER_DYNAMIC => limits.min_storage_buffer_offset_alignment,
+                        _ => return None,
+                    };
+                    Some(DynamicBuffer {
+                        binding: *binding,
+                        alignment: *alignment as u64,
+                        end: info[0].offset + info[0].range,
+                        size: *size,
+                        min_alignment,
+                    })
                 }
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        // Dynamic offsets are consumed in binding order
+        dynamic_buffers.sort_by_key(|b| b.binding);
+
+        let writes = sets.iter()
+            .flat_map(|set| {
+                self.writes.iter().map(move |write| match write {
+                    WriteDescriptor::Buffer { binding, ty, info, .. } => {
+                        *vk::WriteDescriptorSet::builder()
+                            .dst_set(*set)
+                            .dst_binding(*binding)
+                            .descriptor_type(*ty)
+                            .dst_array_element(0)
+                            .buffer_info(info)
+                    }
+                    WriteDescriptor::Image { binding, ty, info } => {
+                        *vk::WriteDescriptorSet::builder()
+                            .dst_set(*set)
+                            .dst_binding(*binding)
+                            .descriptor_type(*ty)
+                            .dst_array_element(0)
+                            .image_info(info)
+                    }
+                })
             })
             .collect::<Vec<_>>();
 
         unsafe { self.device.update_descriptor_sets(&writes, &[]) };
 
-        Ok(DescriptorSet {
-            device: Arc::clone(&self.device),
-            set,
-            dynamic_sizes,
-        })
+        Ok(sets.into_iter()
+            .map(|set| DescriptorSet {
+                device: Arc::clone(&self.device),
+                set,
+                dynamic_buffers: dynamic_buffers.clone(),
+                bindings: bindings.clone(),
+            })
+            .collect())
     }
 }
 
@@ -446,16 +759,90 @@ impl DescriptorAllocator {
 pub struct DescriptorSet {
     device: Arc<Device>,
     set: vk::DescriptorSet,
-    dynamic_sizes: Vec<u32>,
+    dynamic_buffers: Vec<DynamicBuffer>,
+    bindings: Vec<BindingInfo>,
+}
+
+/// A dynamic buffer descriptor of a [`DescriptorSet`], used to validate its dynamic offsets.
+#[derive(Clone, Copy)]
+struct DynamicBuffer {
+    binding: u32,
+    /// Size of an instance of the buffer, dynamic offsets are given in instances.
+    alignment: u64,
+    /// End of the bound range in bytes, without a dynamic offset.
+    end: u64,
+    /// Size of the buffer in bytes.
+    size: u64,
+    /// Required alignment of the dynamic offset in bytes.
+    min_alignment: u64,
 }
 
 impl DescriptorSet {
+    /// Writes an [`Image`] and [`Sampler`] to a single element of an array binding.
+    ///
+    /// Allows filling large arrays of textures one element at a time, for shaders that index
+    /// them dynamically. The element must not be in use by a submitted command buffer, unless
+    /// the binding was created with
+    /// [`UPDATE_AFTER_BIND`](DescriptorBindingFlags::UPDATE_AFTER_BIND).
+    ///
+    /// Returns [`DescriptorError::BindingNotFound`] if the layout of the set has no such binding
+    /// and [`DescriptorError::ArrayIndexOutOfBounds`] if `array_index` is not less than its count.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # let event_loop = winit::event_loop::EventLoop::new();
+    /// # let window = winit::window::WindowBuilder::new().build(&event_loop)?;
+    /// # let device = plate::Device::new(&Default::default(), &Default::default(), Some(&window))?;
+    /// # let cmd_pool = plate::CommandPool::new(&device)?;
+    /// # let textures = [plate::Texture::new(&device, &cmd_pool, 0, 0, &[0])?];
+    /// # let sampler = plate::Sampler::new(&device, &Default::default())?;
+    /// # let pool = plate::DescriptorPool::new(&device, &[], 1)?;
+    /// let layout = plate::DescriptorSetLayout::new(&device, &[plate::LayoutBinding {
+    ///     binding: 0,
+    ///     ty: plate::DescriptorType::COMBINED_IMAGE_SAMPLER,
+    ///     stage: plate::ShaderStage::FRAGMENT,
+    ///     count: 1024,
+    ///     flags: plate::DescriptorBindingFlags::PARTIALLY_BOUND
+    ///         | plate::DescriptorBindingFlags::UPDATE_AFTER_BIND,
+    /// }])?;
+    /// let descriptor_set = plate::DescriptorAllocator::new(&device).allocate(&layout, &pool)?;
+    /// for (i, texture) in textures.iter().enumerate() {
+    ///     descriptor_set.update_image_at(0, i as u32, texture, &sampler, plate::ImageLayout::SHADER_READ_ONLY_OPTIMAL)?;
+    /// }
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn update_image_at(&self, binding: u32, array_index: u32, image: &Image, sampler: &Sampler, layout: ImageLayout) -> Result<(), Error> {
+        let info = self.bindings.iter()
+            .find(|b| b.binding == binding)
+            .ok_or(DescriptorError::BindingNotFound(binding))?;
+        if array_index >= info.count {
+            return Err(DescriptorError::ArrayIndexOutOfBounds { index: array_index, count: info.count }.into())
+        }
+
+        let image_info = [image.descriptor_info(sampler, layout)];
+        let write = vk::WriteDescriptorSet::builder()
+            .dst_set(self.set)
+            .dst_binding(binding)
+            .dst_array_element(array_index)
+            .descriptor_type(info.ty)
+            .image_info(&image_info);
+
+        unsafe { self.device.update_descriptor_sets(&[*write], &[]) };
+        Ok(())
+    }
+
     /// Binds the DescriptorSet.
     /// 
     /// To be used when recording a command buffer, should be used after binding the pipeline. The
     /// pipeline should be created with the same [`DescriptorSetLayout`] as this DescriptorSet.
-    /// `dynamic_offsets` must have the same length as the number of dynamic descriptors in this
-    /// set.
+    /// `dynamic_offsets` are given in instances of the bound buffers, one per dynamic descriptor
+    /// in this set, in binding order.
+    ///
+    /// Returns [`DescriptorError::DynamicOffsetOutOfBounds`] if the number of offsets does not
+    /// match, [`DescriptorError::MisalignedDynamicOffset`] if an offset in bytes is not a multiple
+    /// of the device alignment limit and [`DescriptorError::DynamicOffsetOutOfRange`] if it moves
+    /// a bound range past the end of its buffer.
     /// 
     /// # Examples
     /// 
@@ -479,14 +866,7 @@ impl DescriptorSet {
     /// # Ok::<(), Box<dyn std::error::Error>>(())
     /// ```
     pub fn bind(&self, cmd_buffer: &CommandBuffer, pipeline: &Pipeline, first_set: u32, dynamic_offsets: &[u32]) -> Result<(), Error> {
-        if dynamic_offsets.len() != self.dynamic_sizes.len() {
-            return Err(DescriptorError::DynamicOffsetOutOfBounds { actual: dynamic_offsets.len(), expected: self.dynamic_sizes.len() }.into())
-        }
-
-        let dynamic_offsets = self.dynamic_sizes.iter()
-            .enumerate()
-            .map(|(i, s)| s * dynamic_offsets[i])
-            .collect::<Vec<_>>();
+        let dynamic_offsets = dynamic_byte_offsets(&self.dynamic_buffers, dynamic_offsets)?;
 
         unsafe {
             self.device.cmd_bind_descriptor_sets(
@@ -502,3 +882,40 @@ impl DescriptorSet {
         Ok(())
     }
 }
+
+/// Converts dynamic offsets in instances to bytes, checking their count, alignment and range.
+fn dynamic_byte_offsets(buffers: &[DynamicBuffer], offsets: &[u32]) -> Result<Vec<u32>, DescriptorError> {
+    if offsets.len() != buffers.len() {
+        return Err(DescriptorError::DynamicOffsetOutOfBounds { actual: offsets.len(), expected: buffers.len() })
+    }
+
+    buffers.iter()
+        .zip(offsets)
+        .map(|(buffer, &offset)| {
+            let offset = buffer.alignment * offset as u64;
+            if buffer.min_alignment > 0 && offset & (buffer.min_alignment - 1) != 0 {
+                return Err(DescriptorError::MisalignedDynamicOffset { binding: buffer.binding, offset, alignment: buffer.min_alignment })
+            }
+            match u32::try_from(offset) {
+                Ok(o) if buffer.end + offset <= buffer.size => Ok(o),
+                _ => Err(DescriptorError::DynamicOffsetOutOfRange { binding: buffer.binding, offset, size: buffer.size }),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dynamic_byte_offsets() {
+        let buffers = [DynamicBuffer { binding: 0, alignment: 256, end: 256, size: 512, min_alignment: 256 }];
+        assert_eq!(dynamic_byte_offsets(&buffers, &[1]).unwrap(), [256]);
+        assert!(matches!(dynamic_byte_offsets(&buffers, &[]), Err(DescriptorError::DynamicOffsetOutOfBounds { actual: 0, expected: 1 })));
+        assert!(matches!(dynamic_byte_offsets(&buffers, &[2]), Err(DescriptorError::DynamicOffsetOutOfRange { offset: 512, .. })));
+
+        let unaligned = [DynamicBuffer { alignment: 64, ..buffers[0] }];
+        assert!(matches!(dynamic_byte_offsets(&unaligned, &[1]), Err(DescriptorError::MisalignedDynamicOffset { offset: 64, alignment: 256, .. })));
+    }
+}
@@ -1,4 +1,4 @@
-use std::{ops, sync::Arc};
+use std::{ffi, ops, sync::Arc};
 
 use ash::{extensions::khr, vk};
 
@@ -30,6 +30,10 @@ pub struct Device {
     pub(crate) instance: Instance,
     pub(crate) physical_device: vk::PhysicalDevice,
     pub(crate) queue: Queue,
+    pub(crate) transfer_queue: Queue,
+    pub(crate) features: DeviceFeatures,
+    pub(crate) descriptor_indexing: bool,
+    pub(crate) max_draw_indirect_count: u32,
 }
 
 impl Drop for Device {
@@ -55,6 +59,9 @@ impl Device {
     /// no device is from the preferred [`DeviceType`], it will default to whatever is available.
     /// The [`InstanceParameters`] are used to create the instance.
     ///
+    /// If `window` is `None`, no presentation extensions are enabled and the Device can only be
+    /// used for headless rendering, see [`OffscreenChain`](crate::OffscreenChain).
+    ///
     /// # Exmaple
     ///
     /// ```no_run
@@ -76,36 +83,47 @@ impl Device {
         let queue_properties =
             unsafe { instance.get_physical_device_queue_family_properties(physical_device) };
 
-        let mut graphics_family = None;
-        queue_properties
+        let graphics_family = queue_properties
             .iter()
-            .enumerate()
-            .map(|(i, properties)| {
-                if graphics_family.is_none()
-                    && properties.queue_flags.contains(vk::QueueFlags::GRAPHICS)
-                {
-                    graphics_family = Some(i);
-                }
-                Ok(())
-            })
-            .collect::<Result<_, Error>>()?;
-
+            .position(|properties| properties.queue_flags.contains(vk::QueueFlags::GRAPHICS));
         let queue_family = graphics_family.ok_or(DeviceError::QueueNotFound)? as u32;
+        let transfer_family = transfer_only_family(&queue_properties);
+
+        let queue_infos = [queue_family].into_iter()
+            .chain(transfer_family)
+            .map(|family| *vk::DeviceQueueCreateInfo::builder()
+                .queue_family_index(family)
+                .queue_priorities(&[0.0]))
+            .collect::<Vec<_>>();
+
+        let features = enabled_features(params.features);
+        let mut extensions = match window {
+            Some(_) => vec![khr::Swapchain::name().as_ptr()],
+            None => vec![],
+        };
 
-        let queue_infos = [*vk::DeviceQueueCreateInfo::builder()
-            .queue_family_index(queue_family)
-            .queue_priorities(&[0.0])];
-
-        let features = vk::PhysicalDeviceFeatures::builder();
-        let extensions = [khr::Swapchain::name().as_ptr()];
+        let descriptor_indexing = supports_descriptor_indexing(&instance, physical_device)?;
+        if descriptor_indexing {
+            extensions.push(vk::ExtDescriptorIndexingFn::name().as_ptr());
+            extensions.push(vk::KhrMaintenance3Fn::name().as_ptr());
+        }
 
         let mut draw_params = vk::PhysicalDeviceShaderDrawParametersFeatures::builder()
             .shader_draw_parameters(true);
-        let device_info = vk::DeviceCreateInfo::builder()
+        let mut indexing_features = vk::PhysicalDeviceDescriptorIndexingFeatures::builder()
+            .runtime_descriptor_array(true)
+            .descriptor_binding_partially_bound(true)
+            .descriptor_binding_variable_descriptor_count(true)
+            .descriptor_binding_sampled_image_update_after_bind(true)
+            .shader_sampled_image_array_non_uniform_indexing(true);
+        let mut device_info = vk::DeviceCreateInfo::builder()
             .queue_create_infos(&queue_infos)
             .enabled_features(&features)
             .enabled_extension_names(&extensions)
             .push_next(&mut draw_params);
+        if descriptor_indexing {
+            device_info = device_info.push_next(&mut indexing_features);
+        }
 
         let device = unsafe { instance.create_device(physical_device, &device_info, None)? };
 
@@ -113,15 +131,107 @@ impl Device {
             queue: unsafe { device.get_device_queue(queue_family, 0) },
             family: queue_family,
         };
+        let transfer_queue = match transfer_family {
+            Some(family) => Queue {
+                queue: unsafe { device.get_device_queue(family, 0) },
+                family,
+            },
+            None => queue,
+        };
+
+        let max_draw_indirect_count = match params.features.contains(DeviceFeatures::MULTI_DRAW_INDIRECT) {
+            true => unsafe { instance.get_physical_device_properties(physical_device).limits.max_draw_indirect_count },
+            false => 1,
+        };
 
         Ok(Arc::new(Self {
             device,
             instance,
             physical_device,
             queue,
+            transfer_queue,
+            features: params.features,
+            descriptor_indexing,
+            max_draw_indirect_count,
         }))
     }
 
+    /// Returns whether descriptor indexing is enabled on the Device.
+    ///
+    /// Descriptor indexing is enabled when the physical device supports it, and is required to
+    /// create [`LayoutBindings`](crate::LayoutBinding) with
+    /// [`DescriptorBindingFlags`](crate::DescriptorBindingFlags), such as large arrays of
+    /// textures indexed from the shader.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # let event_loop = winit::event_loop::EventLoop::new();
+    /// # let window = winit::window::WindowBuilder::new().build(&event_loop)?;
+    /// # let device = plate::Device::new(&Default::default(), &Default::default(), Some(&window))?;
+    /// if device.supports_descriptor_indexing() {
+    ///     // use bindless textures..
+    /// }
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn supports_descriptor_indexing(&self) -> bool {
+        self.descriptor_indexing
+    }
+
+    /// Returns the index of the queue family used for graphics and presentation.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # let event_loop = winit::event_loop::EventLoop::new();
+    /// # let window = winit::window::WindowBuilder::new().build(&event_loop)?;
+    /// # let device = plate::Device::new(&Default::default(), &Default::default(), Some(&window))?;
+    /// let family = device.queue_family();
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn queue_family(&self) -> u32 {
+        self.queue.family
+    }
+
+    /// Returns the index of the queue family used for transfers.
+    ///
+    /// This is the same as [`queue_family()`](Self::queue_family()) if the physical device has no
+    /// transfer only queue family, see [`has_transfer_queue()`](Self::has_transfer_queue()).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # let event_loop = winit::event_loop::EventLoop::new();
+    /// # let window = winit::window::WindowBuilder::new().build(&event_loop)?;
+    /// # let device = plate::Device::new(&Default::default(), &Default::default(), Some(&window))?;
+    /// let family = device.transfer_queue_family();
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn transfer_queue_family(&self) -> u32 {
+        self.transfer_queue.family
+    }
+
+    /// Returns whether the Device has a dedicated transfer queue.
+    ///
+    /// When the physical device has a queue family supporting transfers but not graphics or
+    /// compute, a queue of that family is used for transfers, which can then run in parallel with
+    /// rendering. Otherwise transfers are submitted to the graphics queue.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # let event_loop = winit::event_loop::EventLoop::new();
+    /// # let window = winit::window::WindowBuilder::new().build(&event_loop)?;
+    /// # let device = plate::Device::new(&Default::default(), &Default::default(), Some(&window))?;
+    /// if device.has_transfer_queue() {
+    ///     // stream assets while rendering..
+    /// }
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn has_transfer_queue(&self) -> bool {
+        self.transfer_queue.family != self.queue.family
+    }
+
     /// Submit a [`CommandBuffer`] to be executed.
     ///
     /// # Examples
@@ -153,29 +263,115 @@ impl Device {
         fence: Option<&Fence>
     ) -> Result<(), Error> {
         let wait_semaphores = match wait_semaphore {
-            Some(s) => vec![**s],
+            Some(s) => vec![(s, wait_stage)],
             None => vec![],
         };
         let signal_semaphores = match signal_semaphore {
-            Some(s) => vec![**s],
+            Some(s) => vec![s],
             None => vec![],
         };
+        self.queue_submit_batch(&[command_buffer], &wait_semaphores, &signal_semaphores, fence)
+    }
+
+    /// Submit multiple [`CommandBuffers`](CommandBuffer) to be executed in a single submission.
+    ///
+    /// The submission waits on each semaphore of `wait_semaphores` at its paired pipeline stage,
+    /// and signals all `signal_semaphores` and `fence` once every command buffer completed.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # let event_loop = winit::event_loop::EventLoop::new();
+    /// # let window = winit::window::WindowBuilder::new().build(&event_loop)?;
+    /// # let device = plate::Device::new(&Default::default(), &Default::default(), Some(&window))?;
+    /// # let cmd_pool = plate::CommandPool::new(&device)?;
+    /// # let compute_cmd = cmd_pool.alloc_cmd_buffer(plate::CommandBufferLevel::PRIMARY)?;
+    /// # let graphics_cmd = cmd_pool.alloc_cmd_buffer(plate::CommandBufferLevel::PRIMARY)?;
+    /// # let fence = plate::Fence::new(&device, plate::FenceFlags::SIGNALED)?;
+    /// # let compute_sem = plate::Semaphore::new(&device, plate::SemaphoreFlags::empty())?;
+    /// # let acquire_sem = plate::Semaphore::new(&device, plate::SemaphoreFlags::empty())?;
+    /// # let present_sem = plate::Semaphore::new(&device, plate::SemaphoreFlags::empty())?;
+    /// device.queue_submit_batch(&[&compute_cmd], &[], &[&compute_sem], None)?;
+    /// device.queue_submit_batch(
+    ///     &[&graphics_cmd],
+    ///     &[
+    ///         (&compute_sem, plate::PipelineStage::VERTEX_INPUT),
+    ///         (&acquire_sem, plate::PipelineStage::COLOR_ATTACHMENT_OUTPUT),
+    ///     ],
+    ///     &[&present_sem],
+    ///     Some(&fence),
+    /// )?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn queue_submit_batch(
+        &self,
+        command_buffers: &[&CommandBuffer],
+        wait_semaphores: &[(&Semaphore, PipelineStage)],
+        signal_semaphores: &[&Semaphore],
+        fence: Option<&Fence>
+    ) -> Result<(), Error> {
+        self.submit(self.queue, command_buffers, wait_semaphores, signal_semaphores, fence)
+    }
+
+    /// Submit [`CommandBuffers`](CommandBuffer) to the transfer queue.
+    ///
+    /// Works like [`queue_submit_batch()`](Self::queue_submit_batch()), the command buffers must
+    /// be allocated from a [`CommandPool::new_transfer()`](crate::CommandPool::new_transfer())
+    /// pool. Resources written by the transfer queue and used by the graphics queue need a queue
+    /// family ownership transfer, see
+    /// [`BufferMemoryBarrier::queue_families()`](crate::BufferMemoryBarrier::queue_families()),
+    /// unless the Device has no dedicated transfer queue.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # let event_loop = winit::event_loop::EventLoop::new();
+    /// # let window = winit::window::WindowBuilder::new().build(&event_loop)?;
+    /// # let device = plate::Device::new(&Default::default(), &Default::default(), Some(&window))?;
+    /// # let transfer_pool = plate::CommandPool::new_transfer(&device)?;
+    /// # let transfer_cmd = transfer_pool.alloc_cmd_buffer(plate::CommandBufferLevel::PRIMARY)?;
+    /// # let fence = plate::Fence::new(&device, plate::FenceFlags::empty())?;
+    /// device.queue_submit_transfer(&[&transfer_cmd], &[], &[], Some(&fence))?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn queue_submit_transfer(
+        &self,
+        command_buffers: &[&CommandBuffer],
+        wait_semaphores: &[(&Semaphore, PipelineStage)],
+        signal_semaphores: &[&Semaphore],
+        fence: Option<&Fence>
+    ) -> Result<(), Error> {
+        self.submit(self.transfer_queue, command_buffers, wait_semaphores, signal_semaphores, fence)
+    }
+
+    fn submit(
+        &self,
+        queue: Queue,
+        command_buffers: &[&CommandBuffer],
+        wait_semaphores: &[(&Semaphore, PipelineStage)],
+        signal_semaphores: &[&Semaphore],
+        fence: Option<&Fence>
+    ) -> Result<(), Error> {
+        let (wait_semaphores, wait_stages): (Vec<_>, Vec<_>) = wait_semaphores.iter()
+            .map(|(s, stage)| (***s, *stage))
+            .unzip();
+        let signal_semaphores = signal_semaphores.iter()
+            .map(|s| ***s)
+            .collect::<Vec<_>>();
         let fence = match fence {
             Some(f) => **f,
             None => vk::Fence::null(),
         };
-        let wait_stages = match wait_stage {
-            vk::PipelineStageFlags::NONE => vec![],
-            _ => vec![wait_stage],
-        };
-        let command_buffers = [**command_buffer];
+        let command_buffers = command_buffers.iter()
+            .map(|cmd_buffer| ***cmd_buffer)
+            .collect::<Vec<_>>();
         let submit_infos = [*vk::SubmitInfo::builder()
             .wait_semaphores(&wait_semaphores)
             .wait_dst_stage_mask(&wait_stages)
             .signal_semaphores(&signal_semaphores)
             .command_buffers(&command_buffers)];
 
-        Ok(unsafe { self.device.queue_submit(self.queue.queue, &submit_infos, fence)? })
+        Ok(unsafe { self.device.queue_submit(queue.queue, &submit_infos, fence)? })
     }
 
     /// Wait for all device queues to be executed.
@@ -193,7 +389,38 @@ impl Device {
         Ok(unsafe { self.device.device_wait_idle()? })
     }
 
-    pub(crate) fn memory_type_index(&self, mem_requirements: vk::MemoryRequirements, memory_properties: MemoryPropertyFlags) -> Result<usize, Error> {
+    /// Names a Vulkan object, the name is shown in validation messages and graphics debuggers.
+    ///
+    /// Does nothing if the Device was created without
+    /// [`enable_validation_layers`](InstanceParameters::enable_validation_layers).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # let event_loop = winit::event_loop::EventLoop::new();
+    /// # let window = winit::window::WindowBuilder::new().build(&event_loop)?;
+    /// # let device = plate::Device::new(&Default::default(), &Default::default(), Some(&window))?;
+    /// let buffer: plate::Buffer<u32> = plate::Buffer::new( // ..
+    ///     # &device,
+    ///     # 2,
+    ///     # plate::BufferUsageFlags::UNIFORM_BUFFER,
+    ///     # plate::SharingMode::EXCLUSIVE,
+    ///     # plate::MemoryPropertyFlags::HOST_VISIBLE | plate::MemoryPropertyFlags::HOST_COHERENT,
+    /// # )?;
+    /// device.set_debug_name(&buffer, "camera uniforms")?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn set_debug_name<T: DebugObject>(&self, object: &T, name: &str) -> Result<(), Error> {
+        match self.instance.debugger() {
+            Some(debugger) => {
+                let (object_type, handle) = object.debug_handle();
+                debugger.set_object_name(self.device.handle(), object_type, handle, name)
+            },
+            None => Ok(()),
+        }
+    }
+
+    pub(crate) fn memory_type_index(&self, mem_requirements: vk::MemoryRequirements, memory_properties: MemoryPropertyFlags) -> Option<usize> {
         let mem_properties = unsafe { self.instance.get_physical_device_memory_properties(self.physical_device) };
         mem_properties
             .memory_types
@@ -204,18 +431,24 @@ impl Device {
                     && ty.property_flags.contains(memory_properties)
             })
             .map(|(i, _)| i)
-            .ok_or(DeviceError::MemoryTypeNotFound(memory_properties).into())
     }
 }
 
 pub use vk::PhysicalDeviceType as DeviceType;
+pub use vk::ObjectType;
 pub use vk::PipelineStageFlags as PipelineStage;
 
+/// A Vulkan object that can be named with [`Device::set_debug_name()`].
+pub trait DebugObject {
+    /// Returns the type and raw handle of the object.
+    fn debug_handle(&self) -> (ObjectType, u64);
+}
+
 /// Parameters for physical device selection.
 pub struct DeviceParameters {
     /// Will prefer devices of this type.
     pub preferred_type: DeviceType,
-    /// What features the device should support.
+    /// What features the device should support. The features are enabled on the created device.
     pub features: DeviceFeatures,
 }
 
@@ -255,6 +488,38 @@ fn pick_device(
     Ok(**preferred_device)
 }
 
+/// Returns the first queue family supporting transfers but not graphics or compute.
+fn transfer_only_family(queue_properties: &[vk::QueueFamilyProperties]) -> Option<u32> {
+    queue_properties
+        .iter()
+        .position(|properties| {
+            properties.queue_flags.contains(vk::QueueFlags::TRANSFER)
+                && !properties.queue_flags.intersects(vk::QueueFlags::GRAPHICS | vk::QueueFlags::COMPUTE)
+        })
+        .map(|i| i as u32)
+}
+
+/// Checks that the physical device has the descriptor indexing extension and supports every
+/// feature enabled by [`Device::new()`].
+fn supports_descriptor_indexing(instance: &Instance, physical_device: vk::PhysicalDevice) -> Result<bool, Error> {
+    let extensions = unsafe { instance.enumerate_device_extension_properties(physical_device)? };
+    let has_extension = |name: &ffi::CStr| extensions.iter()
+        .any(|e| unsafe { ffi::CStr::from_ptr(e.extension_name.as_ptr()) } == name);
+    if !has_extension(vk::ExtDescriptorIndexingFn::name()) || !has_extension(vk::KhrMaintenance3Fn::name()) {
+        return Ok(false)
+    }
+
+    let mut indexing_features = vk::PhysicalDeviceDescriptorIndexingFeatures::default();
+    let mut features = vk::PhysicalDeviceFeatures2::builder().push_next(&mut indexing_features);
+    unsafe { instance.get_physical_device_features2(physical_device, &mut features) };
+
+    Ok(indexing_features.runtime_descriptor_array == vk::TRUE
+        && indexing_features.descriptor_binding_partially_bound == vk::TRUE
+        && indexing_features.descriptor_binding_variable_descriptor_count == vk::TRUE
+        && indexing_features.descriptor_binding_sampled_image_update_after_bind == vk::TRUE
+        && indexing_features.shader_sampled_image_array_non_uniform_indexing == vk::TRUE)
+}
+
 trait Contains<T> {
     fn contains(&self, _: &T) -> bool;
 }
@@ -355,6 +620,66 @@ impl Contains<DeviceFeatures> for vk::PhysicalDeviceFeatures {
     }
 }
 
+fn enabled_features(features: DeviceFeatures) -> vk::PhysicalDeviceFeatures {
+    vk::PhysicalDeviceFeatures {
+        robust_buffer_access: features.contains(DeviceFeatures::ROBUST_BUFFER_ACCESS) as vk::Bool32,
+        full_draw_index_uint32: features.contains(DeviceFeatures::FULL_DRAW_INDEX_UINT32) as vk::Bool32,
+        image_cube_array: features.contains(DeviceFeatures::IMAGE_CUBE_ARRAY) as vk::Bool32,
+        independent_blend: features.contains(DeviceFeatures::INDEPENDENT_BLEND) as vk::Bool32,
+        geometry_shader: features.contains(DeviceFeatures::GEOMETRY_SHADER) as vk::Bool32,
+        tessellation_shader: features.contains(DeviceFeatures::TESSELLATION_SHADER) as vk::Bool32,
+        sample_rate_shading: features.contains(DeviceFeatures::SAMPLE_RATE_SHADING) as vk::Bool32,
+        dual_src_blend: features.contains(DeviceFeatures::DUAL_SRC_BLEND) as vk::Bool32,
+        logic_op: features.contains(DeviceFeatures::LOGIC_OP) as vk::Bool32,
+        multi_draw_indirect: features.contains(DeviceFeatures::MULTI_DRAW_INDIRECT) as vk::Bool32,
+        draw_indirect_first_instance: features.contains(DeviceFeatures::DRAW_INDIRECT_FIRST_INSTANCE) as vk::Bool32,
+        depth_clamp: features.contains(DeviceFeatures::DEPTH_CLAMP) as vk::Bool32,
+        depth_bias_clamp: features.contains(DeviceFeatures::DEPTH_BIAS_CLAMP) as vk::Bool32,
+        fill_mode_non_solid: features.contains(DeviceFeatures::FILL_MODE_NON_SOLID) as vk::Bool32,
+        depth_bounds: features.contains(DeviceFeatures::DEPTH_BOUNDS) as vk::Bool32,
+        wide_lines: features.contains(DeviceFeatures::WIDE_LINES) as vk::Bool32,
+        large_points: features.contains(DeviceFeatures::LARGE_POINTS) as vk::Bool32,
+        alpha_to_one: features.contains(DeviceFeatures::ALPHA_TO_ONE) as vk::Bool32,
+        multi_viewport: features.contains(DeviceFeatures::MULTI_VIEWPORT) as vk::Bool32,
+        sampler_anisotropy: features.contains(DeviceFeatures::SAMPLER_ANISOTROPY) as vk::Bool32,
+        texture_compression_etc2: features.contains(DeviceFeatures::TEXTURE_COMPRESSION_ETC2) as vk::Bool32,
+        texture_compression_astc_ldr: features.contains(DeviceFeatures::TEXTURE_COMPRESSION_ASTC_LDR) as vk::Bool32,
+        texture_compression_bc: features.contains(DeviceFeatures::TEXTURE_COMPRESSION_BC) as vk::Bool32,
+        occlusion_query_precise: features.contains(DeviceFeatures::OCCLUSION_QUERY_PRECISE) as vk::Bool32,
+        pipeline_statistics_query: features.contains(DeviceFeatures::PIPELINE_STATISTICS_QUERY) as vk::Bool32,
+        vertex_pipeline_stores_and_atomics: features.contains(DeviceFeatures::VERTEX_PIPELINE_STORES_AND_ATOMICS) as vk::Bool32,
+        fragment_stores_and_atomics: features.contains(DeviceFeatures::FRAGMENT_STORES_AND_ATOMICS) as vk::Bool32,
+        shader_tessellation_and_geometry_point_size: features.contains(DeviceFeatures::SHADER_TESSELLATION_AND_GEOMETRY_POINT_SIZE) as vk::Bool32,
+        shader_image_gather_extended: features.contains(DeviceFeatures::SHADER_IMAGE_GATHER_EXTENDED) as vk::Bool32,
+        shader_storage_image_extended_formats: features.contains(DeviceFeatures::SHADER_STORAGE_IMAGE_EXTENDED_FORMATS) as vk::Bool32,
+        shader_storage_image_multisample: features.contains(DeviceFeatures::SHADER_STORAGE_IMAGE_MULTISAMPLE) as vk::Bool32,
+        shader_storage_image_read_without_format: features.contains(DeviceFeatures::SHADER_STORAGE_IMAGE_READ_WITHOUT_FORMAT) as vk::Bool32,
+        shader_storage_image_write_without_format: features.contains(DeviceFeatures::SHADER_STORAGE_IMAGE_WRITE_WITHOUT_FORMAT) as vk::Bool32,
+        shader_uniform_buffer_array_dynamic_indexing: features.contains(DeviceFeatures::SHADER_UNIFORM_BUFFER_ARRAY_DYNAMIC_INDEXING) as vk::Bool32,
+        shader_sampled_image_array_dynamic_indexing: features.contains(DeviceFeatures::SHADER_SAMPLED_IMAGE_ARRAY_DYNAMIC_INDEXING) as vk::Bool32,
+        shader_storage_buffer_array_dynamic_indexing: features.contains(DeviceFeatures::SHADER_STORAGE_BUFFER_ARRAY_DYNAMIC_INDEXING) as vk::Bool32,
+        shader_storage_image_array_dynamic_indexing: features.contains(DeviceFeatures::SHADER_STORAGE_IMAGE_ARRAY_DYNAMIC_INDEXING) as vk::Bool32,
+        shader_clip_distance: features.contains(DeviceFeatures::SHADER_CLIP_DISTANCE) as vk::Bool32,
+        shader_cull_distance: features.contains(DeviceFeatures::SHADER_CULL_DISTANCE) as vk::Bool32,
+        shader_float64: features.contains(DeviceFeatures::SHADER_FLOAT64) as vk::Bool32,
+        shader_int64: features.contains(DeviceFeatures::SHADER_INT64) as vk::Bool32,
+        shader_int16: features.contains(DeviceFeatures::SHADER_INT16) as vk::Bool32,
+        shader_resource_residency: features.contains(DeviceFeatures::SHADER_RESOURCE_RESIDENCY) as vk::Bool32,
+        shader_resource_min_lod: features.contains(DeviceFeatures::SHADER_RESOURCE_MIN_LOD) as vk::Bool32,
+        sparse_binding: features.contains(DeviceFeatures::SPARSE_BINDING) as vk::Bool32,
+        sparse_residency_buffer: features.contains(DeviceFeatures::SPARSE_RESIDENCY_BUFFER) as vk::Bool32,
+        sparse_residency_image2_d: features.contains(DeviceFeatures::SPARSE_RESIDENCY_IMAGE2_D) as vk::Bool32,
+        sparse_residency_image3_d: features.contains(DeviceFeatures::SPARSE_RESIDENCY_IMAGE3_D) as vk::Bool32,
+        sparse_residency2_samples: features.contains(DeviceFeatures::SPARSE_RESIDENCY2_SAMPLES) as vk::Bool32,
+        sparse_residency4_samples: features.contains(DeviceFeatures::SPARSE_RESIDENCY4_SAMPLES) as vk::Bool32,
+        sparse_residency8_samples: features.contains(DeviceFeatures::SPARSE_RESIDENCY8_SAMPLES) as vk::Bool32,
+        sparse_residency16_samples: features.contains(DeviceFeatures::SPARSE_RESIDENCY16_SAMPLES) as vk::Bool32,
+        sparse_residency_aliased: features.contains(DeviceFeatures::SPARSE_RESIDENCY_ALIASED) as vk::Bool32,
+        variable_multisample_rate: features.contains(DeviceFeatures::VARIABLE_MULTISAMPLE_RATE) as vk::Bool32,
+        inherited_queries: features.contains(DeviceFeatures::INHERITED_QUERIES) as vk::Bool32,
+    }
+}
+
 bitflags::bitflags! {
     /// <https://www.khronos.org/registry/vulkan/specs/1.3-extensions/man/html/VkPhysicalDeviceFeatures.html>
     pub struct DeviceFeatures: u64 {
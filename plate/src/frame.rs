@@ -0,0 +1,220 @@
+use std::{cell::Cell, sync::Arc};
+
+use crate::{CommandBuffer, CommandBufferLevel, CommandPool, Device, Error, Fence, FenceFlags, PipelineStage, Semaphore, SemaphoreFlags, Swapchain, SwapchainStatus};
+
+/// Errors from the frame module.
+#[derive(thiserror::Error, Debug)]
+pub enum FrameError {
+    /// The swapchain no longer matches the surface and must be
+    /// [`recreated`](Swapchain::recreate()) before rendering another frame.
+    #[error("The swapchain is out of date")]
+    OutOfDate,
+    /// A [`FrameManager`] needs at least one frame in flight.
+    #[error("A FrameManager needs at least one frame in flight")]
+    NoFramesInFlight,
+}
+
+/// Optional parameters for [`FrameManager`] creation.
+pub struct FrameManagerParameters {
+    /// Number of frames the host can record while the GPU is still rendering previous ones.
+    pub frames_in_flight: usize,
+}
+
+impl Default for FrameManagerParameters {
+    fn default() -> Self {
+        Self {
+            frames_in_flight: 2,
+        }
+    }
+}
+
+struct FrameSync {
+    cmd_buffer: CommandBuffer,
+    fence: Fence,
+    acquire_sem: Semaphore,
+    present_sem: Semaphore,
+}
+
+/// Owns the synchronization primitives and command buffers of multiple frames in flight.
+///
+/// Each frame in flight has its own [`CommandBuffer`], [`Fence`] and acquire and present
+/// [`Semaphores`](Semaphore). [`begin_frame()`](Self::begin_frame()) waits for the GPU to finish
+/// the previous use of the next frame and acquires a swapchain image,
+/// [`Frame::submit_and_present()`] submits the recorded command buffer and presents the image.
+pub struct FrameManager {
+    device: Arc<Device>,
+    frames: Vec<FrameSync>,
+    current: Cell<usize>,
+    #[allow(dead_code)]
+    cmd_pool: CommandPool,
+}
+
+impl FrameManager {
+    /// Creates a FrameManager.
+    ///
+    /// Returns [`FrameError::NoFramesInFlight`] if `frames_in_flight` is 0.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # let event_loop = winit::event_loop::EventLoop::new();
+    /// # let window = winit::window::WindowBuilder::new().build(&event_loop)?;
+    /// # let device = plate::Device::new(&Default::default(), &Default::default(), Some(&window))?;
+    /// let frames = plate::FrameManager::new(&device, &Default::default())?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn new(device: &Arc<Device>, params: &FrameManagerParameters) -> Result<Self, Error> {
+        if params.frames_in_flight == 0 {
+            return Err(FrameError::NoFramesInFlight.into())
+        }
+
+        let cmd_pool = CommandPool::new(device)?;
+        let cmd_buffers = cmd_pool.alloc_cmd_buffers(CommandBufferLevel::PRIMARY, params.frames_in_flight as u32)?;
+
+        let frames = cmd_buffers.into_iter()
+            .map(|cmd_buffer| {
+                Ok(FrameSync {
+                    cmd_buffer,
+                    fence: Fence::new(device, FenceFlags::SIGNALED)?,
+                    acquire_sem: Semaphore::new(device, SemaphoreFlags::empty())?,
+                    present_sem: Semaphore::new(device, SemaphoreFlags::empty())?,
+                })
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        Ok(Self {
+            device: Arc::clone(device),
+            frames,
+            current: Cell::new(0),
+            cmd_pool,
+        })
+    }
+
+    /// Waits for the next frame in flight to be available and acquires a swapchain image.
+    ///
+    /// Returns [`FrameError::OutOfDate`] if the swapchain must be recreated, in which case the
+    /// frame is not started and `begin_frame()` can be called again after the recreation.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # let event_loop = winit::event_loop::EventLoop::new();
+    /// # let window = winit::window::WindowBuilder::new().build(&event_loop)?;
+    /// # let device = plate::Device::new(&Default::default(), &Default::default(), Some(&window))?;
+    /// # let swapchain = plate::Swapchain::new(&device, &window, &Default::default())?;
+    /// # let frames = plate::FrameManager::new(&device, &Default::default())?;
+    /// let frame = frames.begin_frame(&swapchain)?;
+    /// frame.cmd_buffer.record(plate::CommandBufferUsageFlags::empty(), || {
+    ///     // render_pass.begin(&frame.cmd_buffer, &framebuffers[frame.image_index as usize]);
+    ///     // ..
+    /// })?;
+    /// frame.submit_and_present()?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn begin_frame<'a>(&'a self, swapchain: &'a Swapchain) -> Result<Frame<'a>, Error> {
+        let index = self.current.get();
+        let sync = &self.frames[index];
+
+        sync.fence.wait()?;
+
+        let image_index = match swapchain.next_image(&sync.acquire_sem)? {
+            Some((image_index, _)) => image_index,
+            None => return Err(FrameError::OutOfDate.into()),
+        };
+
+        Ok(Frame {
+            manager: self,
+            swapchain,
+            index,
+            image_index,
+            cmd_buffer: &sync.cmd_buffer,
+            submitted: false,
+        })
+    }
+
+    /// Returns the number of frames in flight.
+    pub fn frames_in_flight(&self) -> usize {
+        self.frames.len()
+    }
+}
+
+/// A frame started with [`FrameManager::begin_frame()`].
+///
+/// Dropping a Frame without calling [`submit_and_present()`](Self::submit_and_present()) submits
+/// no work, but still waits on the acquired image and signals the frame fence so the frame in
+/// flight can be used again.
+pub struct Frame<'a> {
+    manager: &'a FrameManager,
+    swapchain: &'a Swapchain,
+    /// Index of the frame in flight, less than [`FrameManager::frames_in_flight()`]. Can be used
+    /// to index per frame resources, such as a [`PerFrameBuffer`](crate::PerFrameBuffer).
+    pub index: usize,
+    /// Index of the acquired swapchain image.
+    pub image_index: u32,
+    /// Command buffer of the frame, to be recorded before
+    /// [`submit_and_present()`](Self::submit_and_present()).
+    pub cmd_buffer: &'a CommandBuffer,
+    submitted: bool,
+}
+
+impl Drop for Frame<'_> {
+    fn drop(&mut self) {
+        if !self.submitted {
+            let _ = self.release();
+        }
+    }
+}
+
+impl<'a> Frame<'a> {
+    /// Submits the command buffer of the frame and presents the swapchain image.
+    ///
+    /// Advances the [`FrameManager`] to the next frame in flight. Returns the [`SwapchainStatus`]
+    /// of the presentation, the swapchain should be [`recreated`](Swapchain::recreate()) if it is
+    /// not [`Optimal`](SwapchainStatus::Optimal).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # let event_loop = winit::event_loop::EventLoop::new();
+    /// # let window = winit::window::WindowBuilder::new().build(&event_loop)?;
+    /// # let device = plate::Device::new(&Default::default(), &Default::default(), Some(&window))?;
+    /// # let swapchain = plate::Swapchain::new(&device, &window, &Default::default())?;
+    /// # let frames = plate::FrameManager::new(&device, &Default::default())?;
+    /// let frame = frames.begin_frame(&swapchain)?;
+    /// // frame.cmd_buffer.record(..)?;
+    /// if frame.submit_and_present()? != plate::SwapchainStatus::Optimal {
+    ///     // swapchain.recreate(&window)?;
+    /// }
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn submit_and_present(mut self) -> Result<SwapchainStatus, Error> {
+        let sync = &self.manager.frames[self.index];
+
+        // If either fails the frame is released on drop
+        sync.fence.reset()?;
+        self.manager.device.queue_submit(
+            &sync.cmd_buffer,
+            PipelineStage::COLOR_ATTACHMENT_OUTPUT,
+            Some(&sync.acquire_sem),
+            Some(&sync.present_sem),
+            Some(&sync.fence),
+        )?;
+        self.submitted = true;
+        self.manager.current.set((self.index + 1) % self.manager.frames.len());
+
+        self.swapchain.present(self.image_index, &sync.present_sem)
+    }
+
+    /// Submits an empty batch waiting on the acquire semaphore and signaling the fence, leaving
+    /// the frame in flight ready for the next [`FrameManager::begin_frame()`].
+    fn release(&self) -> Result<(), Error> {
+        let sync = &self.manager.frames[self.index];
+        sync.fence.reset()?;
+        self.manager.device.queue_submit_batch(
+            &[],
+            &[(&sync.acquire_sem, PipelineStage::ALL_COMMANDS)],
+            &[],
+            Some(&sync.fence),
+        )
+    }
+}
@@ -0,0 +1,137 @@
+use std::sync::Arc;
+
+use crate::{CommandBuffer, DescriptorAllocator, DescriptorPool, DescriptorSet, DescriptorSetLayout, DescriptorType, Device, Error, Image, ImageLayout, LayoutBinding, Pipeline, PipelineParameters, PoolSize, RenderPass, SampleCount, Sampler, ShaderStage};
+
+/// Optional parameters for [`FullscreenPass`] creation.
+pub struct FullscreenPassParameters {
+    /// Index of the [`RenderPass`] subpass in which the pass will be drawn.
+    pub subpass: u32,
+    /// Number of samples per pixel of the subpass attachments.
+    pub rasterization_samples: SampleCount,
+}
+
+impl Default for FullscreenPassParameters {
+    fn default() -> Self {
+        Self {
+            subpass: 0,
+            rasterization_samples: SampleCount::Sample1,
+        }
+    }
+}
+
+/// Draws an input image over the whole render target.
+///
+/// Uses built in shaders that render a single triangle covering the viewport, without vertex
+/// input, and sample the input image with the provided [`Sampler`]. Useful to copy or composite
+/// an image rendered in a previous pass. The input image must be in the
+/// [`SHADER_READ_ONLY_OPTIMAL`](ImageLayout::SHADER_READ_ONLY_OPTIMAL) layout when drawn.
+pub struct FullscreenPass {
+    pipeline: Pipeline,
+    descriptor_set: DescriptorSet,
+    #[allow(dead_code)]
+    descriptor_pool: DescriptorPool,
+    #[allow(dead_code)]
+    set_layout: DescriptorSetLayout,
+}
+
+impl FullscreenPass {
+    /// Creates a FullscreenPass sampling from `image`.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # let event_loop = winit::event_loop::EventLoop::new();
+    /// # let window = winit::window::WindowBuilder::new().build(&event_loop)?;
+    /// # let device = plate::Device::new(&Default::default(), &Default::default(), Some(&window))?;
+    /// # let cmd_pool = plate::CommandPool::new(&device)?;
+    /// # let render_pass = plate::RenderPass::new(&device, &[], &[], &[])?;
+    /// # let (width, height) = (0, 0);
+    /// # let data = [0];
+    /// let texture = plate::Texture::new(&device, &cmd_pool, width, height, &data)?;
+    /// let sampler = plate::Sampler::new(&device, &Default::default())?;
+    /// let fullscreen = plate::FullscreenPass::new(&device, &render_pass, &texture, &sampler, &Default::default())?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn new(
+        device: &Arc<Device>,
+        render_pass: &RenderPass,
+        image: &Image,
+        sampler: &Sampler,
+        params: &FullscreenPassParameters,
+    ) -> Result<Self, Error> {
+        let set_layout = DescriptorSetLayout::new(
+            device,
+            &[LayoutBinding {
+                binding: 0,
+                ty: DescriptorType::COMBINED_IMAGE_SAMPLER,
+                stage: ShaderStage::FRAGMENT,
+                count: 1,
+                ..Default::default()
+            }],
+        )?;
+
+        let descriptor_pool = DescriptorPool::new(
+            device,
+            &[PoolSize {
+                ty: DescriptorType::COMBINED_IMAGE_SAMPLER,
+                count: 1,
+            }],
+            1,
+        )?;
+
+        let descriptor_set = DescriptorAllocator::new(device)
+            .add_image_binding(0, DescriptorType::COMBINED_IMAGE_SAMPLER, image, sampler, ImageLayout::SHADER_READ_ONLY_OPTIMAL)
+            .allocate(&set_layout, &descriptor_pool)?;
+
+        let pipeline = Pipeline::new(
+            device,
+            render_pass,
+            vk_shader_macros::include_glsl!("src/shaders/fullscreen/shader.vert"),
+            vk_shader_macros::include_glsl!("src/shaders/fullscreen/shader.frag"),
+            &PipelineParameters {
+                descriptor_set_layouts: &[&set_layout],
+                depth_test: false,
+                depth_write: false,
+                subpass: params.subpass,
+                rasterization_samples: params.rasterization_samples,
+                ..Default::default()
+            },
+        )?;
+
+        Ok(Self {
+            pipeline,
+            descriptor_set,
+            descriptor_pool,
+            set_layout,
+        })
+    }
+
+    /// Draws the input image over the viewport covering `extent`.
+    ///
+    /// To be used when recording a command buffer, inside the subpass of the [`RenderPass`] the
+    /// FullscreenPass was created with.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # let event_loop = winit::event_loop::EventLoop::new();
+    /// # let window = winit::window::WindowBuilder::new().build(&event_loop)?;
+    /// # let device = plate::Device::new(&Default::default(), &Default::default(), Some(&window))?;
+    /// # let cmd_pool = plate::CommandPool::new(&device)?;
+    /// # let cmd_buffer = cmd_pool.alloc_cmd_buffer(plate::CommandBufferLevel::PRIMARY)?;
+    /// # let render_pass = plate::RenderPass::new(&device, &[], &[], &[])?;
+    /// # let texture = plate::Texture::new(&device, &cmd_pool, 0, 0, &[0])?;
+    /// # let sampler = plate::Sampler::new(&device, &Default::default())?;
+    /// # let fullscreen = plate::FullscreenPass::new(&device, &render_pass, &texture, &sampler, &Default::default())?;
+    /// # let extent = (0, 0);
+    /// // cmd_buffer.record(.., || {
+    ///     fullscreen.draw(&cmd_buffer, extent)?;
+    /// // })?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn draw(&self, cmd_buffer: &CommandBuffer, extent: (u32, u32)) -> Result<(), Error> {
+        self.pipeline.bind(cmd_buffer, extent);
+        self.descriptor_set.bind(cmd_buffer, &self.pipeline, 0, &[])?;
+        cmd_buffer.draw(3, 1, 0, 0)
+    }
+}
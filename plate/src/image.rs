@@ -1,19 +1,90 @@
 use std::sync::Arc;
 
 use ash::vk;
-use crate::{Buffer, Device, command::*, PipelineStage, Format, Error, MemoryPropertyFlags, ImageLayout};
+use crate::{Buffer, DebugObject, Device, DeviceError, DeviceFeatures, command::*, PipelineStage, Format, Error, MemoryPropertyFlags, ImageLayout, ObjectType};
 pub use vk::Filter as Filter;
 pub use vk::SamplerAddressMode as SamplerAddressMode;
+pub use vk::SamplerMipmapMode as SamplerMipmapMode;
 pub use vk::ImageUsageFlags as ImageUsageFlags;
 pub use vk::ImageAspectFlags as ImageAspectFlags;
+pub use vk::ImageTiling as ImageTiling;
+
+/// Errors from the image module.
+#[derive(thiserror::Error, Debug)]
+pub enum ImageError {
+    /// The image was not created with a mutable format, views must use the image format.
+    #[error("The image format {image:?} is not mutable, can not create a view with format {view:?}")]
+    ImmutableFormat {
+        image: Format,
+        view: Format,
+    },
+    /// The format does not support linear filtered blits, required to generate mipmaps.
+    #[error("Format {0:?} does not support linear filtered blits, can not generate mipmaps")]
+    UnsupportedBlitFormat(Format),
+    /// The view format is not size compatible with the image format.
+    #[error("The view format {view:?} is not compatible with the image format {image:?}")]
+    IncompatibleFormat {
+        image: Format,
+        view: Format,
+    },
+    /// The format does not support the requested usage with the requested tiling.
+    #[error("Format {format:?} does not support {usage:?} usage with {tiling:?} tiling")]
+    UnsupportedUsage {
+        format: Format,
+        usage: ImageUsageFlags,
+        tiling: ImageTiling,
+    },
+    /// The texel size of the format is unknown, data can not be uploaded to it.
+    #[error("Format {0:?} is not supported for uploading data")]
+    UnsupportedFormat(Format),
+    /// The data of a layer does not match the size of the image.
+    #[error("Layer {layer} has {size} bytes of data, expected {expected}")]
+    DataSizeMismatch {
+        layer: usize,
+        size: usize,
+        expected: usize,
+    },
+}
+
+/// Number of samples per texel of an [`Image`], used for multisampling.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SampleCount {
+    /// One sample per texel, no multisampling.
+    Sample1,
+    /// Two samples per texel.
+    Sample2,
+    /// Four samples per texel.
+    Sample4,
+    /// Eight samples per texel.
+    Sample8,
+}
+
+impl SampleCount {
+    pub(crate) fn flags(self) -> vk::SampleCountFlags {
+        match self {
+            Self::Sample1 => vk::SampleCountFlags::TYPE_1,
+            Self::Sample2 => vk::SampleCountFlags::TYPE_2,
+            Self::Sample4 => vk::SampleCountFlags::TYPE_4,
+            Self::Sample8 => vk::SampleCountFlags::TYPE_8,
+        }
+    }
+
+    /// Returns the highest sample count not greater than self contained in `supported`.
+    pub(crate) fn clamp(self, supported: vk::SampleCountFlags) -> Self {
+        [Self::Sample8, Self::Sample4, Self::Sample2]
+            .into_iter()
+            .find(|s| *s <= self && supported.contains(s.flags()))
+            .unwrap_or(Self::Sample1)
+    }
+}
 
 /// Filter mode for a [`Sampler`].
 ///
 /// Describes how to interpolate texels.
 pub struct SamplerFilter {
-    /// How to handle magnified texels.
-    pub min: Filter,
     /// How to handle minified texels.
+    pub min: Filter,
+    /// How to handle magnified texels.
     pub mag: Filter,
 }
 
@@ -21,7 +92,7 @@ impl SamplerFilter {
     /// Linear filtering.
     pub const LINEAR: Self = Self { min: Filter::LINEAR, mag: Filter::LINEAR };
     /// Nearest filtering.
-    pub const NEAREST: Self = Self { min: Filter::NEAREST, mag: Filter::LINEAR };
+    pub const NEAREST: Self = Self { min: Filter::NEAREST, mag: Filter::NEAREST };
     /// Cubic filtering.
     pub const CUBIC_EXT: Self = Self { min: Filter::CUBIC_EXT, mag: Filter::CUBIC_EXT };
 }
@@ -59,6 +130,18 @@ pub struct SamplerParameters {
     pub filter: SamplerFilter,
     /// Address mode for the sampler.
     pub address_mode: SamplerAddress,
+    /// How to interpolate between mip levels.
+    pub mipmap_mode: SamplerMipmapMode,
+    /// Maximum anisotropy level, or `None` to disable anisotropic filtering.
+    ///
+    /// The value is clamped to the device `max_sampler_anisotropy` limit. Anisotropic filtering
+    /// requires the [`SAMPLER_ANISOTROPY`](DeviceFeatures::SAMPLER_ANISOTROPY) feature, if the
+    /// [`Device`] was not created with it, anisotropic filtering is silently disabled.
+    pub anisotropy: Option<f32>,
+    /// Minimum mip level of detail to sample from.
+    pub min_lod: f32,
+    /// Maximum mip level of detail to sample from, [`vk::LOD_CLAMP_NONE`] to use all levels.
+    pub max_lod: f32,
 }
 
 impl Default for SamplerParameters {
@@ -66,6 +149,10 @@ impl Default for SamplerParameters {
         Self {
             filter: SamplerFilter::LINEAR,
             address_mode: SamplerAddress::REPEAT,
+            mipmap_mode: SamplerMipmapMode::LINEAR,
+            anisotropy: None,
+            min_lod: 0.0,
+            max_lod: vk::LOD_CLAMP_NONE,
         }
     }
 }
@@ -97,27 +184,35 @@ impl Sampler {
     /// # Ok::<(), Box<dyn std::error::Error>>(())
     /// ```
     pub fn new(device: &Arc<Device>, params: &SamplerParameters) -> Result<Self, Error> {
+        let anisotropy = match params.anisotropy {
+            Some(anisotropy) if device.features.contains(DeviceFeatures::SAMPLER_ANISOTROPY) => {
+                let limits = unsafe { device.instance.get_physical_device_properties(device.physical_device).limits };
+                Some(anisotropy.clamp(1.0, limits.max_sampler_anisotropy))
+            }
+            _ => None,
+        };
+
         let sampler_info = vk::SamplerCreateInfo::builder()
-            .mag_filter(params.filter.min)
-            .min_filter(params.filter.mag)
+            .mag_filter(params.filter.mag)
+            .min_filter(params.filter.min)
             .address_mode_u(params.address_mode.u)
             .address_mode_v(params.address_mode.v)
             .address_mode_w(params.address_mode.w)
-            .anisotropy_enable(false)
-            .max_anisotropy(1.0)
+            .anisotropy_enable(anisotropy.is_some())
+            .max_anisotropy(anisotropy.unwrap_or(1.0))
             .border_color(vk::BorderColor::INT_OPAQUE_BLACK)
             .unnormalized_coordinates(false)
             .compare_enable(false)
             .compare_op(vk::CompareOp::ALWAYS)
-            .mipmap_mode(vk::SamplerMipmapMode::LINEAR)
+            .mipmap_mode(params.mipmap_mode)
             .mip_lod_bias(0.0)
-            .min_lod(0.0)
-            .max_lod(0.0);
+            .min_lod(params.min_lod)
+            .max_lod(params.max_lod);
 
         let sampler = unsafe { device.create_sampler(&sampler_info, None)? };
 
         Ok(Self {
-            device: Arc::clone(&device),
+            device: Arc::clone(device),
             sampler,
         })
     }
@@ -126,15 +221,23 @@ impl Sampler {
 /// Represents a 2 dimesional array of data.
 pub struct Image {
     device: Arc<Device>,
-    image: vk::Image,
+    pub(crate) image: vk::Image,
     mem: Option<vk::DeviceMemory>,
     pub(crate) view: vk::ImageView,
+    pub(crate) aspect: ImageAspectFlags,
+    flags: vk::ImageCreateFlags,
     /// The format of the image.
     pub format: Format,
     /// The width of the image.
     pub width: u32,
     /// The height of the image.
     pub height: u32,
+    /// The number of samples per texel of the image.
+    pub samples: SampleCount,
+    /// The number of mip levels of the image.
+    pub mip_levels: u32,
+    /// The number of array layers of the image, 6 for cubemaps.
+    pub array_layers: u32,
 }
 
 impl Drop for Image {
@@ -149,6 +252,12 @@ impl Drop for Image {
     }
 }
 
+impl DebugObject for Image {
+    fn debug_handle(&self) -> (ObjectType, u64) {
+        (ObjectType::IMAGE, vk::Handle::as_raw(self.image))
+    }
+}
+
 impl Image {
     /// Creates a Image.
     ///
@@ -172,26 +281,32 @@ impl Image {
     /// # Ok::<(), Box<dyn std::error::Error>>(())
     /// ```
     pub fn new(device: &Arc<Device>, width: u32, height: u32, format: Format, layout: ImageLayout, usage: ImageUsageFlags, image_aspect: ImageAspectFlags) -> Result<Self, Error> {
+        Self::with_options(device, width, height, format, usage, image_aspect, &ImageOptions { layout, ..Default::default() })
+    }
+
+    pub(crate) fn with_options(device: &Arc<Device>, width: u32, height: u32, format: Format, usage: ImageUsageFlags, image_aspect: ImageAspectFlags, options: &ImageOptions) -> Result<Self, Error> {
         let image_info = vk::ImageCreateInfo::builder()
+            .flags(options.flags)
             .image_type(vk::ImageType::TYPE_2D)
             .extent(vk::Extent3D {
                 width,
                 height,
                 depth: 1,
             })
-            .mip_levels(1)
-            .array_layers(1)
+            .mip_levels(options.mip_levels)
+            .array_layers(options.array_layers)
             .format(format)
-            .tiling(vk::ImageTiling::OPTIMAL)
+            .tiling(options.tiling)
             .initial_layout(ImageLayout::UNDEFINED)
             .usage(usage)
             .sharing_mode(vk::SharingMode::EXCLUSIVE)
-            .samples(vk::SampleCountFlags::TYPE_1);
+            .samples(options.samples.flags());
 
         let image = unsafe { device.create_image(&image_info, None)? };
 
         let mem_requirements = unsafe { device.get_image_memory_requirements(image) };
-        let mem_type_index = device.memory_type_index(mem_requirements, MemoryPropertyFlags::DEVICE_LOCAL)?;
+        let mem_type_index = device.memory_type_index(mem_requirements, MemoryPropertyFlags::DEVICE_LOCAL)
+            .ok_or(DeviceError::MemoryTypeNotFound(MemoryPropertyFlags::DEVICE_LOCAL))?;
 
         let alloc_info = vk::MemoryAllocateInfo::builder()
             .allocation_size(mem_requirements.size)
@@ -201,27 +316,82 @@ impl Image {
         unsafe { device.bind_image_memory(image, mem, 0)? };
 
         let cmd_pool = CommandPool::new(device)?;
-        if layout != ImageLayout::UNDEFINED {
-            transition_layout(device, image, &cmd_pool, vk::ImageLayout::UNDEFINED, layout)?;
+        if options.layout != ImageLayout::UNDEFINED {
+            transition_layout(device, image, &cmd_pool, image_aspect, vk::ImageLayout::UNDEFINED, options.layout, options.mip_levels)?;
         }
 
-        Self::from_vk_image(device, image, Some(mem), width, height, format, image_aspect)
+        let view = Self::image_view(device, image, image_aspect, format, options.mip_levels, options.array_layers, options.flags)?;
+
+        Ok(Self {
+            device: Arc::clone(device),
+            image,
+            mem: Some(mem),
+            view,
+            aspect: image_aspect,
+            flags: options.flags,
+            format,
+            width,
+            height,
+            samples: options.samples,
+            mip_levels: options.mip_levels,
+            array_layers: options.array_layers,
+        })
     }
 
     pub(crate) fn from_vk_image(device: &Arc<Device>, image: vk::Image, mem: Option<vk::DeviceMemory>, width: u32, height: u32, format: Format, image_aspect: ImageAspectFlags) -> Result<Self, Error> {
-        let view = Self::image_view(device, image, image_aspect, format)?;
+        let view = Self::image_view(device, image, image_aspect, format, 1, 1, vk::ImageCreateFlags::empty())?;
 
         Ok(Self {
-            device: Arc::clone(&device),
+            device: Arc::clone(device),
             image,
             mem,
             view,
+            aspect: image_aspect,
+            flags: vk::ImageCreateFlags::empty(),
             format,
             width,
             height,
+            samples: SampleCount::Sample1,
+            mip_levels: 1,
+            array_layers: 1,
         })
     }
 
+    /// Transitions all the mip levels of the image from `old_layout` to `new_layout`.
+    ///
+    /// Records a [`pipeline_barrier()`](CommandBuffer::pipeline_barrier()) with the accesses and
+    /// stages the image is typically used with in each layout, such as
+    /// [`SHADER_READ`](vk::AccessFlags::SHADER_READ) in the fragment shader for
+    /// [`SHADER_READ_ONLY_OPTIMAL`](ImageLayout::SHADER_READ_ONLY_OPTIMAL). Use the pipeline barrier
+    /// directly for finer control. To be used when recording a [`CommandBuffer`].
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # let event_loop = winit::event_loop::EventLoop::new();
+    /// # let window = winit::window::WindowBuilder::new().build(&event_loop)?;
+    /// # let device = plate::Device::new(&Default::default(), &Default::default(), Some(&window))?;
+    /// # let cmd_pool = plate::CommandPool::new(&device)?;
+    /// # let cmd_buffer = cmd_pool.alloc_cmd_buffer(plate::CommandBufferLevel::PRIMARY)?;
+    /// # let texture = plate::Texture::new(&device, &cmd_pool, 0, 0, &[0])?;
+    /// cmd_buffer.record(plate::CommandBufferUsageFlags::empty(), || {
+    ///     texture.transition_layout(&cmd_buffer, plate::ImageLayout::SHADER_READ_ONLY_OPTIMAL, plate::ImageLayout::TRANSFER_DST_OPTIMAL);
+    ///     // update texture..
+    ///     texture.transition_layout(&cmd_buffer, plate::ImageLayout::TRANSFER_DST_OPTIMAL, plate::ImageLayout::SHADER_READ_ONLY_OPTIMAL);
+    /// })?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn transition_layout(&self, cmd_buffer: &CommandBuffer, old_layout: ImageLayout, new_layout: ImageLayout) {
+        let (src_access, src_stage) = layout_access(old_layout);
+        let (dst_access, dst_stage) = layout_access(new_layout);
+
+        let barrier = ImageMemoryBarrier::new(self)
+            .src_access(src_access)
+            .dst_access(dst_access)
+            .layouts(old_layout, new_layout);
+        cmd_buffer.pipeline_barrier(src_stage, dst_stage, &[], &[barrier]);
+    }
+
     pub(crate) fn descriptor_info(&self, sampler: &Sampler, layout: ImageLayout) -> vk::DescriptorImageInfo {
         *vk::DescriptorImageInfo::builder()
             .image_layout(layout)
@@ -229,7 +399,7 @@ impl Image {
             .sampler(sampler.sampler)
     }
 
-    fn image_view(device: &Arc<Device>, image: vk::Image, image_aspect: ImageAspectFlags, format: Format) -> Result<vk::ImageView, Error> {
+    fn image_view(device: &Arc<Device>, image: vk::Image, image_aspect: ImageAspectFlags, format: Format, mip_levels: u32, array_layers: u32, flags: vk::ImageCreateFlags) -> Result<vk::ImageView, Error> {
         let components = vk::ComponentMapping {
             r: vk::ComponentSwizzle::IDENTITY,
             g: vk::ComponentSwizzle::IDENTITY,
@@ -240,13 +410,19 @@ impl Image {
         let subresource_range = *vk::ImageSubresourceRange::builder()
             .aspect_mask(image_aspect)
             .base_mip_level(0)
-            .level_count(1)
+            .level_count(mip_levels)
             .base_array_layer(0)
-            .layer_count(1);
+            .layer_count(array_layers);
+
+        let view_type = match array_layers {
+            6 if flags.contains(vk::ImageCreateFlags::CUBE_COMPATIBLE) => vk::ImageViewType::CUBE,
+            1 => vk::ImageViewType::TYPE_2D,
+            _ => vk::ImageViewType::TYPE_2D_ARRAY,
+        };
 
         let view_info = vk::ImageViewCreateInfo::builder()
             .image(image)
-            .view_type(vk::ImageViewType::TYPE_2D)
+            .view_type(view_type)
             .format(format)
             .components(components)
             .subresource_range(subresource_range);
@@ -255,8 +431,142 @@ impl Image {
     }
 }
 
+pub(crate) struct ImageOptions {
+    pub layout: ImageLayout,
+    pub flags: vk::ImageCreateFlags,
+    pub samples: SampleCount,
+    pub mip_levels: u32,
+    pub array_layers: u32,
+    pub tiling: ImageTiling,
+}
+
+impl Default for ImageOptions {
+    fn default() -> Self {
+        Self {
+            layout: ImageLayout::UNDEFINED,
+            flags: vk::ImageCreateFlags::empty(),
+            samples: SampleCount::Sample1,
+            mip_levels: 1,
+            array_layers: 1,
+            tiling: ImageTiling::OPTIMAL,
+        }
+    }
+}
+
+/// A view over the data of an [`Image`] with a different format.
+///
+/// The [`Image`] must be created with a mutable format, such as a [`Texture`] with
+/// `mutable_format` set in the [`TextureParameters`], and the view format must be size compatible
+/// with the image format. Can be used to sample the same data as sRGB or UNORM, for example.
+pub struct ImageView {
+    device: Arc<Device>,
+    pub(crate) view: vk::ImageView,
+    /// The format of the view.
+    pub format: Format,
+}
+
+impl Drop for ImageView {
+    fn drop(&mut self) {
+        unsafe {
+            self.device.destroy_image_view(self.view, None);
+        }
+    }
+}
+
+impl ImageView {
+    /// Creates a ImageView.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # let event_loop = winit::event_loop::EventLoop::new();
+    /// # let window = winit::window::WindowBuilder::new().build(&event_loop)?;
+    /// # let device = plate::Device::new(&Default::default(), &Default::default(), Some(&window))?;
+    /// # let cmd_pool = plate::CommandPool::new(&device)?;
+    /// # let (width, height) = (0, 0);
+    /// # let data = [0];
+    /// let texture = plate::Texture::from_data(&device, &cmd_pool, width, height, &data, &plate::TextureParameters {
+    ///     format: plate::Format::R8G8B8A8_UNORM,
+    ///     mutable_format: true,
+    ///     ..Default::default()
+    /// })?;
+    /// // Sample the same data with automatic sRGB decoding
+    /// let view = plate::ImageView::new(&device, &texture, plate::Format::R8G8B8A8_SRGB)?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn new(device: &Arc<Device>, image: &Image, format: Format) -> Result<Self, Error> {
+        if format != image.format {
+            if !image.flags.contains(vk::ImageCreateFlags::MUTABLE_FORMAT) {
+                return Err(ImageError::ImmutableFormat { image: image.format, view: format }.into())
+            }
+            match (format_size(image.format), format_size(format)) {
+                (Some(a), Some(b)) if a == b => (),
+                _ => return Err(ImageError::IncompatibleFormat { image: image.format, view: format }.into()),
+            }
+        }
+
+        let view = Image::image_view(device, image.image, image.aspect, format, image.mip_levels, image.array_layers, image.flags)?;
+
+        Ok(Self {
+            device: Arc::clone(device),
+            view,
+            format,
+        })
+    }
+
+    pub(crate) fn descriptor_info(&self, sampler: &Sampler, layout: ImageLayout) -> vk::DescriptorImageInfo {
+        *vk::DescriptorImageInfo::builder()
+            .image_layout(layout)
+            .image_view(self.view)
+            .sampler(sampler.sampler)
+    }
+}
+
+/// Optional parameters for [`Texture`] creation.
+pub struct TextureParameters {
+    /// Format of the texture data.
+    pub format: Format,
+    /// Whether [`ImageViews`](ImageView) with a different, compatible, format can be created from
+    /// the texture.
+    pub mutable_format: bool,
+    /// Whether to generate a full chain of mip levels from the texture data.
+    ///
+    /// The levels are generated with linear filtered blits, the format must support
+    /// [`SAMPLED_IMAGE_FILTER_LINEAR`](vk::FormatFeatureFlags::SAMPLED_IMAGE_FILTER_LINEAR).
+    /// Textures created without data only allocate the mip levels.
+    pub mipmaps: bool,
+    /// How the texture will be used. Textures created from data are always usable as a transfer
+    /// source and destination.
+    pub usage: ImageUsageFlags,
+    /// Tiling arrangement of the texels in memory.
+    pub tiling: ImageTiling,
+    /// Layout the texture is transitioned to after creation. Ignored by textures created from
+    /// data, which are left in the
+    /// [`SHADER_READ_ONLY_OPTIMAL`](ImageLayout::SHADER_READ_ONLY_OPTIMAL) layout.
+    pub initial_layout: ImageLayout,
+}
+
+impl Default for TextureParameters {
+    fn default() -> Self {
+        Self {
+            format: Format::R8G8B8A8_SRGB,
+            mutable_format: false,
+            mipmaps: false,
+            usage: ImageUsageFlags::TRANSFER_DST | ImageUsageFlags::SAMPLED,
+            tiling: ImageTiling::OPTIMAL,
+            initial_layout: ImageLayout::UNDEFINED,
+        }
+    }
+}
+
 /// Holds a [`Image`] with texture data in it.
-pub struct Texture(Image);
+pub struct Texture(pub(crate) Image);
+
+impl DebugObject for Texture {
+    fn debug_handle(&self) -> (ObjectType, u64) {
+        self.0.debug_handle()
+    }
+}
 
 impl std::ops::Deref for Texture {
     type Target = Image;
@@ -269,6 +579,8 @@ impl std::ops::Deref for Texture {
 impl Texture {
     /// Creates a Texture from a &[u8].
     ///
+    /// The data is expected to be in the R8G8B8A8_SRGB format.
+    ///
     /// # Examples
     ///
     /// ```no_run
@@ -282,48 +594,248 @@ impl Texture {
     /// # Ok::<(), Box<dyn std::error::Error>>(())
     /// ```
     pub fn new(device: &Arc<Device>, cmd_pool: &CommandPool, width: u32, height: u32, data: &[u8]) -> Result<Self, Error> {
-        let staging = Buffer::new(
-            device,
-            (width * height * 4) as usize,
-            vk::BufferUsageFlags::TRANSFER_SRC,
-            vk::SharingMode::EXCLUSIVE,
-            vk::MemoryPropertyFlags::HOST_VISIBLE | vk::MemoryPropertyFlags::HOST_COHERENT,
-        )?;
+        Self::from_data(device, cmd_pool, width, height, data, &Default::default())
+    }
+
+    /// Creates a Texture from a &[u8] with the given [`TextureParameters`].
+    ///
+    /// The data is expected to be in the format specified in `params`. Returns an error if the
+    /// texel size of the format is unknown or the data is not `width * height` texels long.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # let event_loop = winit::event_loop::EventLoop::new();
+    /// # let window = winit::window::WindowBuilder::new().build(&event_loop)?;
+    /// # let device = plate::Device::new(&Default::default(), &Default::default(), Some(&window))?;
+    /// # let cmd_pool = plate::CommandPool::new(&device)?;
+    /// # let (width, height) = (0, 0);
+    /// # let data = [0];
+    /// let image = plate::Texture::from_data(&device, &cmd_pool, width, height, &data, &plate::TextureParameters {
+    ///     format: plate::Format::R8G8B8A8_UNORM,
+    ///     ..Default::default()
+    /// })?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn from_data(device: &Arc<Device>, cmd_pool: &CommandPool, width: u32, height: u32, data: &[u8], params: &TextureParameters) -> Result<Self, Error> {
+        Self::from_layers(device, cmd_pool, width, height, &[data], vk::ImageCreateFlags::empty(), params)
+    }
 
-        let mut mapped = staging.map()?;
-        mapped.write(data);
-        let staging = mapped.unmap();
+    /// Creates a cubemap Texture from the data of its 6 faces.
+    ///
+    /// The faces are square, `size` texels wide, in the +X, -X, +Y, -Y, +Z, -Z order and the data
+    /// is expected to be in the R8G8B8A8_SRGB format. The texture has 6 array layers and is
+    /// sampled with a `samplerCube` in the shader.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # let event_loop = winit::event_loop::EventLoop::new();
+    /// # let window = winit::window::WindowBuilder::new().build(&event_loop)?;
+    /// # let device = plate::Device::new(&Default::default(), &Default::default(), Some(&window))?;
+    /// # let cmd_pool = plate::CommandPool::new(&device)?;
+    /// # let size = 0;
+    /// # let (right, left, top, bottom, front, back) = ([0], [0], [0], [0], [0], [0]);
+    /// let skybox = plate::Texture::new_cubemap(&device, &cmd_pool, size, &[&right, &left, &top, &bottom, &front, &back])?;
+    /// assert_eq!(skybox.array_layers, 6);
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn new_cubemap(device: &Arc<Device>, cmd_pool: &CommandPool, size: u32, faces: &[&[u8]; 6]) -> Result<Self, Error> {
+        Self::cubemap_from_data(device, cmd_pool, size, faces, &Default::default())
+    }
+
+    /// Creates a cubemap Texture from the data of its 6 faces with the given
+    /// [`TextureParameters`].
+    ///
+    /// See [`new_cubemap()`](Self::new_cubemap()). The data is expected to be in the format
+    /// specified in `params`.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # let event_loop = winit::event_loop::EventLoop::new();
+    /// # let window = winit::window::WindowBuilder::new().build(&event_loop)?;
+    /// # let device = plate::Device::new(&Default::default(), &Default::default(), Some(&window))?;
+    /// # let cmd_pool = plate::CommandPool::new(&device)?;
+    /// # let size = 0;
+    /// # let faces: [&[u8]; 6] = [&[0], &[0], &[0], &[0], &[0], &[0]];
+    /// let skybox = plate::Texture::cubemap_from_data(&device, &cmd_pool, size, &faces, &plate::TextureParameters {
+    ///     format: plate::Format::R16G16B16A16_SFLOAT,
+    ///     ..Default::default()
+    /// })?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn cubemap_from_data(device: &Arc<Device>, cmd_pool: &CommandPool, size: u32, faces: &[&[u8]; 6], params: &TextureParameters) -> Result<Self, Error> {
+        Self::from_layers(device, cmd_pool, size, size, faces, vk::ImageCreateFlags::CUBE_COMPATIBLE, params)
+    }
+
+    fn from_layers(device: &Arc<Device>, cmd_pool: &CommandPool, width: u32, height: u32, layers: &[&[u8]], flags: vk::ImageCreateFlags, params: &TextureParameters) -> Result<Self, Error> {
+        let texture = Self::for_upload(device, width, height, layers.len() as u32, flags, params)?;
+
+        let staging = layer_staging(device, width, height, params.format, layers)?;
+        let (mip_levels, array_layers) = (texture.mip_levels, texture.array_layers);
+
+        transition_layout(device, texture.image, cmd_pool, ImageAspectFlags::COLOR, vk::ImageLayout::UNDEFINED, vk::ImageLayout::TRANSFER_DST_OPTIMAL, mip_levels)?;
+        staging.copy_to_image(texture.image, width, height, array_layers, cmd_pool)?;
+        match mip_levels {
+            1 => transition_layout(device, texture.image, cmd_pool, ImageAspectFlags::COLOR, vk::ImageLayout::TRANSFER_DST_OPTIMAL, vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL, 1)?,
+            _ => generate_mipmaps(device, texture.image, cmd_pool, width, height, mip_levels, array_layers)?,
+        }
+
+        Ok(texture)
+    }
+
+    /// Creates a Texture in the UNDEFINED layout to be filled with a buffer to image copy,
+    /// checking the format can be blitted if mipmaps are requested.
+    pub(crate) fn for_upload(device: &Arc<Device>, width: u32, height: u32, array_layers: u32, flags: vk::ImageCreateFlags, params: &TextureParameters) -> Result<Self, Error> {
+        if params.mipmaps {
+            let props = unsafe { device.instance.get_physical_device_format_properties(device.physical_device, params.format) };
+            let required = vk::FormatFeatureFlags::BLIT_SRC | vk::FormatFeatureFlags::BLIT_DST | vk::FormatFeatureFlags::SAMPLED_IMAGE_FILTER_LINEAR;
+            if !props.optimal_tiling_features.contains(required) {
+                return Err(ImageError::UnsupportedBlitFormat(params.format).into())
+            }
+        }
+
+        Self::with_layers(device, width, height, array_layers, flags, &TextureParameters {
+            format: params.format,
+            mutable_format: params.mutable_format,
+            mipmaps: params.mipmaps,
+            usage: params.usage | ImageUsageFlags::TRANSFER_SRC | ImageUsageFlags::TRANSFER_DST,
+            tiling: params.tiling,
+            initial_layout: ImageLayout::UNDEFINED,
+        })
+    }
+
+    /// Creates a Texture with the given [`TextureParameters`], without uploading any data.
+    ///
+    /// Useful for textures filled on the GPU, such as render targets with the
+    /// [`COLOR_ATTACHMENT`](ImageUsageFlags::COLOR_ATTACHMENT) usage or storage images with the
+    /// [`STORAGE`](ImageUsageFlags::STORAGE) usage. The texture has the
+    /// [`COLOR`](ImageAspectFlags::COLOR) aspect. Returns [`ImageError::UnsupportedUsage`] if the
+    /// format does not support the requested usage.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # let event_loop = winit::event_loop::EventLoop::new();
+    /// # let window = winit::window::WindowBuilder::new().build(&event_loop)?;
+    /// # let device = plate::Device::new(&Default::default(), &Default::default(), Some(&window))?;
+    /// # let (width, height) = (0, 0);
+    /// let target = plate::Texture::with_params(&device, width, height, &plate::TextureParameters {
+    ///     format: plate::Format::R16G16_SFLOAT,
+    ///     usage: plate::ImageUsageFlags::COLOR_ATTACHMENT | plate::ImageUsageFlags::SAMPLED,
+    ///     ..Default::default()
+    /// })?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn with_params(device: &Arc<Device>, width: u32, height: u32, params: &TextureParameters) -> Result<Self, Error> {
+        Self::with_layers(device, width, height, 1, vk::ImageCreateFlags::empty(), params)
+    }
+
+    fn with_layers(device: &Arc<Device>, width: u32, height: u32, array_layers: u32, flags: vk::ImageCreateFlags, params: &TextureParameters) -> Result<Self, Error> {
+        let props = unsafe { device.instance.get_physical_device_format_properties(device.physical_device, params.format) };
+        let supported = match params.tiling {
+            ImageTiling::LINEAR => props.linear_tiling_features,
+            _ => props.optimal_tiling_features,
+        };
+        let required = [
+            (ImageUsageFlags::SAMPLED, vk::FormatFeatureFlags::SAMPLED_IMAGE),
+            (ImageUsageFlags::STORAGE, vk::FormatFeatureFlags::STORAGE_IMAGE),
+            (ImageUsageFlags::COLOR_ATTACHMENT, vk::FormatFeatureFlags::COLOR_ATTACHMENT),
+        ].into_iter()
+            .filter(|(usage, _)| params.usage.contains(*usage))
+            .fold(vk::FormatFeatureFlags::empty(), |acc, (_, feature)| acc | feature);
+        if !supported.contains(required) {
+            return Err(ImageError::UnsupportedUsage { format: params.format, usage: params.usage, tiling: params.tiling }.into())
+        }
 
-        let image = Image::new(
+        let flags = match params.mutable_format {
+            true => flags | vk::ImageCreateFlags::MUTABLE_FORMAT,
+            false => flags,
+        };
+
+        let mip_levels = match params.mipmaps {
+            true => u32::BITS - width.max(height).max(1).leading_zeros(),
+            false => 1,
+        };
+
+        let image = Image::with_options(
             device,
             width,
             height,
-            Format::R8G8B8A8_SRGB,
-            ImageLayout::UNDEFINED,
-            ImageUsageFlags::TRANSFER_DST | ImageUsageFlags::SAMPLED,
+            params.format,
+            params.usage,
             ImageAspectFlags::COLOR,
+            &ImageOptions {
+                layout: params.initial_layout,
+                flags,
+                mip_levels,
+                array_layers,
+                tiling: params.tiling,
+                ..Default::default()
+            },
         )?;
 
-        transition_layout(device, image.image, cmd_pool, vk::ImageLayout::UNDEFINED, vk::ImageLayout::TRANSFER_DST_OPTIMAL)?;
-        staging.copy_to_image(image.image, width, height, cmd_pool)?;
-        transition_layout(device, image.image, cmd_pool, vk::ImageLayout::TRANSFER_DST_OPTIMAL, vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL)?;
-
         Ok(Self(image))
     }
 }
 
-fn transition_layout(device: &Arc<Device>, image: vk::Image, cmd_pool: &CommandPool, old_layout: vk::ImageLayout, new_layout: vk::ImageLayout) -> Result<(), Error> {
-    let (src_access, src_stage) = match old_layout {
-        vk::ImageLayout::UNDEFINED => (vk::AccessFlags::empty(), vk::PipelineStageFlags::TOP_OF_PIPE),
-        vk::ImageLayout::TRANSFER_DST_OPTIMAL => (vk::AccessFlags::TRANSFER_WRITE, vk::PipelineStageFlags::TRANSFER),
-        _ => (vk::AccessFlags::empty(), vk::PipelineStageFlags::TOP_OF_PIPE),
-    };
+/// Creates a host visible staging buffer holding `layers` tightly packed, one after the other.
+pub(crate) fn layer_staging(device: &Arc<Device>, width: u32, height: u32, format: Format, layers: &[&[u8]]) -> Result<Buffer<u8>, Error> {
+    let texel_size = format_size(format).ok_or(ImageError::UnsupportedFormat(format))?;
+    let layer_size = width as usize * height as usize * texel_size;
+    if let Some((layer, data)) = layers.iter().enumerate().find(|(_, data)| data.len() != layer_size) {
+        return Err(ImageError::DataSizeMismatch { layer, size: data.len(), expected: layer_size }.into())
+    }
 
-    let (dst_access, dst_stage) = match new_layout {
-        vk::ImageLayout::TRANSFER_DST_OPTIMAL => (vk::AccessFlags::TRANSFER_WRITE, vk::PipelineStageFlags::TRANSFER),
-        vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL => (vk::AccessFlags::SHADER_READ, vk::PipelineStageFlags::FRAGMENT_SHADER),
-        _ => (vk::AccessFlags::empty(), vk::PipelineStageFlags::BOTTOM_OF_PIPE),
-    };
+    let staging = Buffer::new(
+        device,
+        layer_size * layers.len(),
+        vk::BufferUsageFlags::TRANSFER_SRC,
+        vk::SharingMode::EXCLUSIVE,
+        vk::MemoryPropertyFlags::HOST_VISIBLE | vk::MemoryPropertyFlags::HOST_COHERENT,
+    )?;
+
+    let mut mapped = staging.map()?;
+    layers.iter()
+        .enumerate()
+        .for_each(|(i, data)| mapped.write_index(data, i * layer_size));
+    Ok(mapped.unmap())
+}
+
+pub(crate) fn depth_format(device: &Arc<Device>) -> Option<Format> {
+    [
+        Format::D32_SFLOAT,
+        Format::D32_SFLOAT_S8_UINT,
+        Format::D24_UNORM_S8_UINT
+    ].into_iter()
+        .find(|format| {
+            let props = unsafe { device.instance.get_physical_device_format_properties(device.physical_device, *format) };
+            props.optimal_tiling_features.contains(vk::FormatFeatureFlags::DEPTH_STENCIL_ATTACHMENT)
+        })
+}
+
+/// Returns the size in bytes of a texel of an uncompressed color format.
+pub(crate) fn format_size(format: Format) -> Option<usize> {
+    match format {
+        Format::R8_UNORM | Format::R8_SNORM | Format::R8_UINT | Format::R8_SINT | Format::R8_SRGB => Some(1),
+        Format::R8G8_UNORM | Format::R8G8_SNORM | Format::R8G8_UINT | Format::R8G8_SINT | Format::R8G8_SRGB
+            | Format::R16_UNORM | Format::R16_SNORM | Format::R16_UINT | Format::R16_SINT | Format::R16_SFLOAT => Some(2),
+        Format::R8G8B8A8_UNORM | Format::R8G8B8A8_SNORM | Format::R8G8B8A8_UINT | Format::R8G8B8A8_SINT | Format::R8G8B8A8_SRGB
+            | Format::B8G8R8A8_UNORM | Format::B8G8R8A8_SNORM | Format::B8G8R8A8_UINT | Format::B8G8R8A8_SINT | Format::B8G8R8A8_SRGB
+            | Format::A8B8G8R8_UNORM_PACK32 | Format::A8B8G8R8_SRGB_PACK32 | Format::A2B10G10R10_UNORM_PACK32
+            | Format::R16G16_UNORM | Format::R16G16_SNORM | Format::R16G16_UINT | Format::R16G16_SINT | Format::R16G16_SFLOAT
+            | Format::R32_UINT | Format::R32_SINT | Format::R32_SFLOAT => Some(4),
+        Format::R16G16B16A16_UNORM | Format::R16G16B16A16_SNORM | Format::R16G16B16A16_UINT | Format::R16G16B16A16_SINT | Format::R16G16B16A16_SFLOAT
+            | Format::R32G32_UINT | Format::R32G32_SINT | Format::R32G32_SFLOAT => Some(8),
+        Format::R32G32B32A32_UINT | Format::R32G32B32A32_SINT | Format::R32G32B32A32_SFLOAT => Some(16),
+        _ => None,
+    }
+}
+
+fn transition_layout(device: &Arc<Device>, image: vk::Image, cmd_pool: &CommandPool, aspect: ImageAspectFlags, old_layout: vk::ImageLayout, new_layout: vk::ImageLayout, mip_levels: u32) -> Result<(), Error> {
+    let (src_access, src_stage) = layout_access(old_layout);
+    let (dst_access, dst_stage) = layout_access(new_layout);
 
     let cmd_buffer = cmd_pool.alloc_cmd_buffer(CommandBufferLevel::PRIMARY)?;
     cmd_buffer.record(CommandBufferUsageFlags::ONE_TIME_SUBMIT, || {
@@ -334,11 +846,11 @@ fn transition_layout(device: &Arc<Device>, image: vk::Image, cmd_pool: &CommandP
             .dst_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
             .image(image)
             .subresource_range(vk::ImageSubresourceRange {
-                aspect_mask: vk::ImageAspectFlags::COLOR,
+                aspect_mask: aspect,
                 base_mip_level: 0,
                 base_array_layer: 0,
-                layer_count: 1,
-                level_count: 1,
+                layer_count: vk::REMAINING_ARRAY_LAYERS,
+                level_count: mip_levels,
             })
             .src_access_mask(src_access)
             .dst_access_mask(dst_access);
@@ -351,5 +863,114 @@ fn transition_layout(device: &Arc<Device>, image: vk::Image, cmd_pool: &CommandP
     })?;
 
     device.queue_submit(&cmd_buffer, PipelineStage::empty(), None, None, None)?;
-    Ok(unsafe { device.queue_wait_idle(device.queue.queue)? })
+    unsafe { device.queue_wait_idle(device.queue.queue)? };
+    Ok(())
+}
+
+/// Returns the accesses and pipeline stages an image in `layout` is typically used with.
+fn layout_access(layout: ImageLayout) -> (vk::AccessFlags, PipelineStage) {
+    match layout {
+        ImageLayout::UNDEFINED => (vk::AccessFlags::empty(), PipelineStage::TOP_OF_PIPE),
+        ImageLayout::TRANSFER_SRC_OPTIMAL => (vk::AccessFlags::TRANSFER_READ, PipelineStage::TRANSFER),
+        ImageLayout::TRANSFER_DST_OPTIMAL => (vk::AccessFlags::TRANSFER_WRITE, PipelineStage::TRANSFER),
+        ImageLayout::SHADER_READ_ONLY_OPTIMAL => (vk::AccessFlags::SHADER_READ, PipelineStage::FRAGMENT_SHADER),
+        ImageLayout::COLOR_ATTACHMENT_OPTIMAL => (
+            vk::AccessFlags::COLOR_ATTACHMENT_READ | vk::AccessFlags::COLOR_ATTACHMENT_WRITE,
+            PipelineStage::COLOR_ATTACHMENT_OUTPUT,
+        ),
+        ImageLayout::DEPTH_STENCIL_ATTACHMENT_OPTIMAL => (
+            vk::AccessFlags::DEPTH_STENCIL_ATTACHMENT_READ | vk::AccessFlags::DEPTH_STENCIL_ATTACHMENT_WRITE,
+            PipelineStage::EARLY_FRAGMENT_TESTS | PipelineStage::LATE_FRAGMENT_TESTS,
+        ),
+        ImageLayout::PRESENT_SRC_KHR => (vk::AccessFlags::empty(), PipelineStage::BOTTOM_OF_PIPE),
+        _ => (vk::AccessFlags::MEMORY_READ | vk::AccessFlags::MEMORY_WRITE, PipelineStage::ALL_COMMANDS),
+    }
+}
+
+/// Fills the mip levels of all the layers of `image` by blitting each level to the next, starting
+/// from level 0.
+///
+/// All levels must be in the TRANSFER_DST_OPTIMAL layout and are left in the
+/// SHADER_READ_ONLY_OPTIMAL layout.
+fn generate_mipmaps(device: &Arc<Device>, image: vk::Image, cmd_pool: &CommandPool, width: u32, height: u32, mip_levels: u32, array_layers: u32) -> Result<(), Error> {
+    let cmd_buffer = cmd_pool.alloc_cmd_buffer(CommandBufferLevel::PRIMARY)?;
+    cmd_buffer.record(CommandBufferUsageFlags::ONE_TIME_SUBMIT, || {
+        record_mipmaps(device, &cmd_buffer, image, width, height, mip_levels, array_layers);
+    })?;
+
+    device.queue_submit(&cmd_buffer, PipelineStage::empty(), None, None, None)?;
+    unsafe { device.queue_wait_idle(device.queue.queue)? };
+    Ok(())
+}
+
+/// Records the commands of [`generate_mipmaps()`] to `cmd_buffer`.
+pub(crate) fn record_mipmaps(device: &Arc<Device>, cmd_buffer: &CommandBuffer, image: vk::Image, width: u32, height: u32, mip_levels: u32, array_layers: u32) {
+    let barrier = |level, old_layout, new_layout, src_access, dst_access| {
+        *vk::ImageMemoryBarrier::builder()
+            .old_layout(old_layout)
+            .new_layout(new_layout)
+            .src_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+            .dst_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+            .image(image)
+            .subresource_range(vk::ImageSubresourceRange {
+                aspect_mask: vk::ImageAspectFlags::COLOR,
+                base_mip_level: level,
+                base_array_layer: 0,
+                layer_count: array_layers,
+                level_count: 1,
+            })
+            .src_access_mask(src_access)
+            .dst_access_mask(dst_access)
+    };
+
+    let (mut level_width, mut level_height) = (width as i32, height as i32);
+
+    for level in 1..mip_levels {
+        let to_src = barrier(level - 1, vk::ImageLayout::TRANSFER_DST_OPTIMAL, vk::ImageLayout::TRANSFER_SRC_OPTIMAL, vk::AccessFlags::TRANSFER_WRITE, vk::AccessFlags::TRANSFER_READ);
+        unsafe { device.cmd_pipeline_barrier(
+            **cmd_buffer,
+            vk::PipelineStageFlags::TRANSFER, vk::PipelineStageFlags::TRANSFER, vk::DependencyFlags::empty(),
+            &[], &[], &[to_src]
+        ) };
+
+        let (next_width, next_height) = ((level_width / 2).max(1), (level_height / 2).max(1));
+        let blit = vk::ImageBlit {
+            src_subresource: vk::ImageSubresourceLayers {
+                aspect_mask: vk::ImageAspectFlags::COLOR,
+                mip_level: level - 1,
+                base_array_layer: 0,
+                layer_count: array_layers,
+            },
+            src_offsets: [vk::Offset3D { x: 0, y: 0, z: 0 }, vk::Offset3D { x: level_width, y: level_height, z: 1 }],
+            dst_subresource: vk::ImageSubresourceLayers {
+                aspect_mask: vk::ImageAspectFlags::COLOR,
+                mip_level: level,
+                base_array_layer: 0,
+                layer_count: array_layers,
+            },
+            dst_offsets: [vk::Offset3D { x: 0, y: 0, z: 0 }, vk::Offset3D { x: next_width, y: next_height, z: 1 }],
+        };
+        unsafe { device.cmd_blit_image(
+            **cmd_buffer,
+            image, vk::ImageLayout::TRANSFER_SRC_OPTIMAL,
+            image, vk::ImageLayout::TRANSFER_DST_OPTIMAL,
+            &[blit], vk::Filter::LINEAR,
+        ) };
+
+        let to_read = barrier(level - 1, vk::ImageLayout::TRANSFER_SRC_OPTIMAL, vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL, vk::AccessFlags::TRANSFER_READ, vk::AccessFlags::SHADER_READ);
+        unsafe { device.cmd_pipeline_barrier(
+            **cmd_buffer,
+            vk::PipelineStageFlags::TRANSFER, vk::PipelineStageFlags::FRAGMENT_SHADER, vk::DependencyFlags::empty(),
+            &[], &[], &[to_read]
+        ) };
+
+        (level_width, level_height) = (next_width, next_height);
+    }
+
+    let last_to_read = barrier(mip_levels - 1, vk::ImageLayout::TRANSFER_DST_OPTIMAL, vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL, vk::AccessFlags::TRANSFER_WRITE, vk::AccessFlags::SHADER_READ);
+    unsafe { device.cmd_pipeline_barrier(
+        **cmd_buffer,
+        vk::PipelineStageFlags::TRANSFER, vk::PipelineStageFlags::FRAGMENT_SHADER, vk::DependencyFlags::empty(),
+        &[], &[], &[last_to_read]
+    ) };
 }
@@ -7,7 +7,7 @@ use crate::{debug, Error, Debugger};
 #[derive(thiserror::Error, Debug)]
 pub enum InstanceError {
     /// Error trying to create a C string because of a nul byte.
-    #[error("Error creating C string: {0}")]
+    #[error("Error creating C string")]
     NulError(#[from] ffi::NulError),
 }
 
@@ -71,7 +71,6 @@ impl Default for InstanceParameters {
 pub(crate) struct Instance {
     instance: ash::Instance,
     pub(crate) entry: ash::Entry,
-    #[allow(dead_code)]
     debugger: Option<Debugger>,
 }
 
@@ -174,4 +173,9 @@ impl Instance {
             debugger,
         })
     }
+
+    /// Returns the Debugger if validation layers are enabled.
+    pub(crate) fn debugger(&self) -> Option<&Debugger> {
+        self.debugger.as_ref()
+    }
 }
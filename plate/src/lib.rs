@@ -22,6 +22,8 @@ pub mod instance;
 pub use instance::*;
 pub mod pipeline;
 pub use pipeline::*;
+pub mod shader;
+pub use shader::*;
 pub(crate) mod surface;
 pub(crate) use surface::*;
 pub mod swapchain;
@@ -34,24 +36,91 @@ pub mod image;
 pub use image::*;
 pub mod rendering;
 pub use rendering::*;
+pub mod offscreen;
+pub use offscreen::*;
+pub mod fullscreen;
+pub use fullscreen::*;
+pub mod frame;
+pub use frame::*;
+pub mod query;
+pub use query::*;
+pub mod upload;
+pub use upload::*;
 
 pub use ash::vk;
 
 pub use ash::vk::Format;
 pub use ash::vk::MemoryPropertyFlags;
 
+/// Errors returned by the library.
+///
+/// Each module has its own error type, wrapped by a variant of the same name and returned by
+/// [`source()`](std::error::Error::source()). Failures from Vulkan calls are mapped to a named
+/// variant for the common error codes, and to [`VulkanError`](Error::VulkanError) for the rest.
 #[derive(thiserror::Error, Debug)]
 pub enum Error {
-    #[error("{0}")]
-    VulkanError(#[from] ash::vk::Result),
-    #[error("{0}")]
+    /// A host memory allocation failed.
+    #[error("Out of host memory")]
+    OutOfHostMemory,
+    /// A device memory allocation failed.
+    #[error("Out of device memory")]
+    OutOfDeviceMemory,
+    /// The device was lost because of a hardware or driver failure, or a timeout, and can no
+    /// longer be used.
+    #[error("The device was lost")]
+    DeviceLost,
+    /// The surface is no longer available, such as after the window is destroyed.
+    #[error("The surface was lost")]
+    SurfaceLost,
+    /// A required instance layer or extension is not available.
+    #[error("A required Vulkan layer or extension is not present")]
+    NotPresent,
+    /// The driver is not compatible with the requested Vulkan version, or no driver is
+    /// installed.
+    #[error("Incompatible Vulkan driver")]
+    IncompatibleDriver,
+    /// A Vulkan call failed with any other error code.
+    #[error("Vulkan error: {0}")]
+    VulkanError(ash::vk::Result),
+    #[error("Device error")]
     DeviceError(#[from] DeviceError),
-    #[error("{0}")]
+    #[error("Swapchain error")]
     SwapchainError(#[from] SwapchainError),
-    #[error("{0}")]
+    #[error("Instance error")]
     InstanceError(#[from] InstanceError),
-    #[error("{0}")]
+    #[error("Descriptor error")]
     DescriptorError(#[from] DescriptorError),
+    #[error("Pipeline error")]
+    PipelineError(#[from] PipelineError),
+    #[error("Offscreen error")]
+    OffscreenError(#[from] OffscreenError),
+    #[error("Image error")]
+    ImageError(#[from] ImageError),
+    #[error("Frame error")]
+    FrameError(#[from] FrameError),
+    #[error("Buffer error")]
+    BufferError(#[from] BufferError),
+    #[error("Shader error")]
+    ShaderError(#[from] ShaderError),
+    #[error("Query error")]
+    QueryError(#[from] QueryError),
+    #[error("Command error")]
+    CommandError(#[from] CommandError),
+}
+
+impl From<ash::vk::Result> for Error {
+    fn from(result: ash::vk::Result) -> Self {
+        use ash::vk::Result as R;
+        match result {
+            R::ERROR_OUT_OF_HOST_MEMORY => Self::OutOfHostMemory,
+            R::ERROR_OUT_OF_DEVICE_MEMORY => Self::OutOfDeviceMemory,
+            R::ERROR_DEVICE_LOST => Self::DeviceLost,
+            R::ERROR_SURFACE_LOST_KHR => Self::SurfaceLost,
+            R::ERROR_LAYER_NOT_PRESENT | R::ERROR_EXTENSION_NOT_PRESENT | R::ERROR_FEATURE_NOT_PRESENT => Self::NotPresent,
+            R::ERROR_INCOMPATIBLE_DRIVER => Self::IncompatibleDriver,
+            result => Self::VulkanError(result),
+        }
+    }
 }
 
 #[cfg(feature = "macros")]
@@ -0,0 +1,240 @@
+use std::{cell::Cell, sync::Arc};
+
+use ash::vk;
+
+use crate::{image::{depth_format, format_size}, Buffer, BufferUsageFlags, CommandPool, Device, Error, Fence, FenceFlags, Format, Image, ImageAspectFlags, ImageLayout, ImageUsageFlags, MemoryPropertyFlags, Semaphore, SharingMode, SwapchainStatus};
+
+/// Errors from the offscreen module.
+#[derive(thiserror::Error, Debug)]
+pub enum OffscreenError {
+    /// None of the available image formats match the depth requirements.
+    #[error("No suitable depth format is available")]
+    NoSuitableDepthFormat,
+    /// The image format can not be downloaded to the host.
+    #[error("Images of format {0:?} can not be downloaded")]
+    UnsupportedDownloadFormat(Format),
+}
+
+/// Optional parameters for [`OffscreenChain`] creation.
+pub struct OffscreenChainParameters {
+    /// Number of images in the chain.
+    pub image_count: u32,
+    /// Format of the color images.
+    pub format: Format,
+    /// Whether to create a depth image matching the image extent.
+    pub depth_image: bool,
+}
+
+impl Default for OffscreenChainParameters {
+    fn default() -> Self {
+        Self {
+            image_count: 2,
+            format: Format::R8G8B8A8_UNORM,
+            depth_image: false,
+        }
+    }
+}
+
+/// A ring of color images to render to without a window.
+///
+/// Mimics the [`Swapchain`](crate::Swapchain) API so headless applications can use the same
+/// rendering loop as windowed ones, but [`present()`](Self::present()) marks the image as ready
+/// to be [`downloaded`](Self::download()) instead of showing it on the screen.
+///
+/// The images are created with [`COLOR_ATTACHMENT`](ImageUsageFlags::COLOR_ATTACHMENT) and
+/// [`TRANSFER_SRC`](ImageUsageFlags::TRANSFER_SRC) usage, the [`RenderPass`](crate::RenderPass)
+/// rendering to them should have [`TRANSFER_SRC_OPTIMAL`](ImageLayout::TRANSFER_SRC_OPTIMAL) as
+/// the final layout of the color attachment.
+pub struct OffscreenChain {
+    device: Arc<Device>,
+
+    extent: vk::Extent2D,
+    next: Cell<u32>,
+    fences: Vec<Fence>,
+
+    pub images: Vec<Image>,
+    /// Depth image with the same extent as the color images, if requested in the
+    /// [`OffscreenChainParameters`].
+    pub depth_image: Option<Image>,
+    pub format: Format,
+    pub depth_format: Format,
+}
+
+impl OffscreenChain {
+    /// Creates a OffscreenChain.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # let device = plate::Device::new(&Default::default(), &Default::default(), None)?;
+    /// let offscreen = plate::OffscreenChain::new(&device, 800, 600, &Default::default())?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn new(device: &Arc<Device>, width: u32, height: u32, params: &OffscreenChainParameters) -> Result<Self, Error> {
+        let images = (0..params.image_count)
+            .map(|_| {
+                Image::new(
+                    device,
+                    width,
+                    height,
+                    params.format,
+                    ImageLayout::UNDEFINED,
+                    ImageUsageFlags::COLOR_ATTACHMENT | ImageUsageFlags::TRANSFER_SRC,
+                    ImageAspectFlags::COLOR,
+                )
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let fences = (0..params.image_count)
+            .map(|_| Fence::new(device, FenceFlags::SIGNALED))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let depth_format = depth_format(device)
+            .ok_or(OffscreenError::NoSuitableDepthFormat)?;
+
+        let depth_image = match params.depth_image {
+            true => Some(Image::new(
+                device,
+                width,
+                height,
+                depth_format,
+                ImageLayout::UNDEFINED,
+                ImageUsageFlags::DEPTH_STENCIL_ATTACHMENT,
+                ImageAspectFlags::DEPTH,
+            )?),
+            false => None,
+        };
+
+        Ok(Self {
+            device: Arc::clone(device),
+            extent: vk::Extent2D { width, height },
+            next: Cell::new(0),
+            fences,
+            images,
+            depth_image,
+            format: params.format,
+            depth_format,
+        })
+    }
+
+    /// Acquires the next image of the ring.
+    ///
+    /// Returns the index of the next image and the status of the chain, which is always
+    /// [`Optimal`](SwapchainStatus::Optimal). Will signal the provided semaphore when done.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # let device = plate::Device::new(&Default::default(), &Default::default(), None)?;
+    /// # let offscreen = plate::OffscreenChain::new(&device, 800, 600, &Default::default())?;
+    /// # let acquire_sem = plate::Semaphore::new(&device, plate::SemaphoreFlags::empty())?;
+    /// let (image_index, _) = offscreen.next_image(&acquire_sem)?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn next_image(&self, semaphore: &Semaphore) -> Result<(u32, SwapchainStatus), Error> {
+        let image_index = self.next.get();
+        self.next.set((image_index + 1) % self.images.len() as u32);
+
+        self.fences[image_index as usize].wait()?;
+
+        let signal_semaphores = [**semaphore];
+        let submit_infos = [*vk::SubmitInfo::builder().signal_semaphores(&signal_semaphores)];
+        unsafe { ash::Device::queue_submit(&self.device, self.device.queue.queue, &submit_infos, vk::Fence::null())? };
+
+        Ok((image_index, SwapchainStatus::Optimal))
+    }
+
+    /// Marks the image at `image_index` as ready to be downloaded.
+    ///
+    /// Will wait on wait_semaphore. Returns the status of the chain, which is always
+    /// [`Optimal`](SwapchainStatus::Optimal).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # let device = plate::Device::new(&Default::default(), &Default::default(), None)?;
+    /// # let offscreen = plate::OffscreenChain::new(&device, 800, 600, &Default::default())?;
+    /// # let present_sem = plate::Semaphore::new(&device, plate::SemaphoreFlags::empty())?;
+    /// let image_index = 0;
+    /// offscreen.present(image_index, &present_sem)?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn present(&self, image_index: u32, wait_semaphore: &Semaphore) -> Result<SwapchainStatus, Error> {
+        let fence = &self.fences[image_index as usize];
+        fence.reset()?;
+
+        let wait_semaphores = [**wait_semaphore];
+        let wait_stages = [vk::PipelineStageFlags::ALL_COMMANDS];
+        let submit_infos = [*vk::SubmitInfo::builder()
+            .wait_semaphores(&wait_semaphores)
+            .wait_dst_stage_mask(&wait_stages)];
+        unsafe { ash::Device::queue_submit(&self.device, self.device.queue.queue, &submit_infos, **fence)? };
+
+        Ok(SwapchainStatus::Optimal)
+    }
+
+    /// Copies the contents of the image at `image_index` to the host.
+    ///
+    /// Blocks until the image is [`presented`](Self::present()). The image must be in the
+    /// [`TRANSFER_SRC_OPTIMAL`](ImageLayout::TRANSFER_SRC_OPTIMAL) layout. Returns the tightly
+    /// packed texels of the image, row by row.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # let device = plate::Device::new(&Default::default(), &Default::default(), None)?;
+    /// # let offscreen = plate::OffscreenChain::new(&device, 800, 600, &Default::default())?;
+    /// # let cmd_pool = plate::CommandPool::new(&device)?;
+    /// let image_index = 0;
+    /// let pixels = offscreen.download(image_index, &cmd_pool)?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn download(&self, image_index: u32, cmd_pool: &CommandPool) -> Result<Vec<u8>, Error> {
+        let texel_size = format_size(self.format).ok_or(OffscreenError::UnsupportedDownloadFormat(self.format))?;
+
+        self.fences[image_index as usize].wait()?;
+
+        let buffer: Buffer<u8> = Buffer::new(
+            &self.device,
+            (self.extent.width * self.extent.height) as usize * texel_size,
+            BufferUsageFlags::TRANSFER_DST,
+            SharingMode::EXCLUSIVE,
+            MemoryPropertyFlags::HOST_VISIBLE | MemoryPropertyFlags::HOST_COHERENT,
+        )?;
+        buffer.copy_from_image(self.images[image_index as usize].image, self.extent.width, self.extent.height, cmd_pool)?;
+
+        let mapped = buffer.map()?;
+        let data = mapped.read()?;
+        mapped.unmap();
+
+        Ok(data)
+    }
+
+    /// Returns the aspect ration of the extent.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # let device = plate::Device::new(&Default::default(), &Default::default(), None)?;
+    /// # let offscreen = plate::OffscreenChain::new(&device, 800, 600, &Default::default())?;
+    /// let aspect_ratio = offscreen.aspect_ratio();
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn aspect_ratio(&self) -> f32 {
+        (self.extent.width as f32) / (self.extent.height as f32)
+    }
+
+    /// Returns the extent of the images.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # let device = plate::Device::new(&Default::default(), &Default::default(), None)?;
+    /// # let offscreen = plate::OffscreenChain::new(&device, 800, 600, &Default::default())?;
+    /// let (width, height) = offscreen.extent();
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn extent(&self) -> (u32, u32) {
+        (self.extent.width, self.extent.height)
+    }
+}
@@ -1,12 +1,84 @@
-use std::{ffi, sync::Arc};
+use std::{ffi, mem, path::Path, sync::Arc};
 
 use ash::vk;
 
-use crate::{DescriptorSetLayout, Device, Format, Error, CommandBuffer, RenderPass};
+use crate::{DebugObject, DescriptorSetLayout, Device, DeviceFeatures, Format, ObjectType, ShaderModule, Error, CommandBuffer, RenderPass, SampleCount, ShaderStage};
 
 pub use vk::VertexInputRate as InputRate;
 pub use vk::FrontFace;
 pub use vk::CullModeFlags;
+pub use vk::CompareOp;
+pub use vk::PolygonMode;
+pub use vk::BlendFactor;
+pub use vk::BlendOp;
+pub use vk::ColorComponentFlags;
+
+/// Errors from the pipeline module.
+#[derive(thiserror::Error, Debug)]
+pub enum PipelineError {
+    /// The physical device does not support the format as a vertex attribute.
+    #[error("Format {0:?} is not supported as a vertex attribute")]
+    UnsupportedVertexFormat(Format),
+    /// The subpass index is not present in the [`RenderPass`].
+    #[error("Subpass {subpass} is out of bounds, the render pass has {count} subpasses")]
+    SubpassOutOfBounds {
+        subpass: u32,
+        count: usize,
+    },
+    /// A push constant range exceeds the device `max_push_constants_size` limit.
+    #[error("Push constant range ending at {end} bytes exceeds the device limit of {max} bytes")]
+    PushConstantRangeTooLarge {
+        end: u32,
+        max: u32,
+    },
+    /// The pushed data is not contained in a push constant range of the pipeline for the given
+    /// stages.
+    #[error("Push constant data from {offset} to {end} bytes is not in a push constant range for stages {stage:?}")]
+    PushConstantOutOfRange {
+        stage: ShaderStage,
+        offset: u32,
+        end: u32,
+    },
+    /// The viewport and scissor are baked into the pipeline with a zero extent.
+    #[error("Pipeline extent {0:?} has a zero dimension and dynamic_viewport is disabled")]
+    ZeroExtent((u32, u32)),
+    /// The pushed stages do not include every stage of a push constant range overlapping the data.
+    #[error("Push constants for stages {stage:?} overlap a range used by stages {range_stage:?}, all of them must be pushed")]
+    PushConstantStageMismatch {
+        stage: ShaderStage,
+        range_stage: ShaderStage,
+    },
+    /// The offset or size of push constants is not a multiple of 4.
+    #[error("Push constant offset {offset} and size {size} must be multiples of 4")]
+    MisalignedPushConstants {
+        offset: u32,
+        size: u32,
+    },
+    /// The rasterization samples do not match the attachments of the subpass.
+    #[error("Pipeline has {pipeline:?} rasterization samples but the subpass attachments have {render_pass:?}")]
+    SampleCountMismatch {
+        pipeline: SampleCount,
+        render_pass: SampleCount,
+    },
+    /// The pipeline requires a [`DeviceFeatures`] that was not enabled when creating the
+    /// [`Device`].
+    #[error("Pipeline requires the device features {0:?}")]
+    MissingFeature(DeviceFeatures),
+    /// The line width is outside of the device `line_width_range` limit.
+    #[error("Line width {width} is outside of the supported range from {min} to {max}")]
+    LineWidthOutOfRange {
+        width: f32,
+        min: f32,
+        max: f32,
+    },
+    /// The number of blend states is neither 0, 1 or the number of color attachments of the
+    /// subpass.
+    #[error("Pipeline has {count} blend states but the subpass has {attachments} color attachments")]
+    BlendStateCountMismatch {
+        count: usize,
+        attachments: usize,
+    },
+}
 
 /// Vertex binding information.
 ///
@@ -17,6 +89,14 @@ pub struct VertexBindingDescription(vk::VertexInputBindingDescription);
 ///
 /// Describes the offset of a field of a vector, its format and the corresponding binding and
 /// location on the shader.
+///
+/// Besides float formats, packed normalized formats can be used to save memory. Fields with
+/// formats like [`R8G8B8A8_UNORM`](Format::R8G8B8A8_UNORM) or
+/// [`B8G8R8A8_UNORM`](Format::B8G8R8A8_UNORM) are expected to be a `[u8; 4]` in the Rust
+/// struct, in the same component order as the format, and are read as a `vec4` with values in
+/// the 0..1 range in the shader. A `u32` can be used with
+/// [`A8B8G8R8_UNORM_PACK32`](Format::A8B8G8R8_UNORM_PACK32), where the components are packed
+/// from the least significant byte.
 pub struct VertexAttributeDescription(vk::VertexInputAttributeDescription);
 
 impl VertexBindingDescription {
@@ -79,6 +159,196 @@ pub trait VertexDescription {
     fn attribute_descriptions() -> Vec<VertexAttributeDescription>;
 }
 
+/// Describes a range of push constants accessible from the shader stages.
+///
+/// Every device supports at least 128 bytes of push constants. [`Pipeline::new()`] returns an
+/// error if the offset or size are not multiples of 4.
+#[derive(Clone, Copy)]
+pub struct PushConstantRange {
+    /// The shader stages that will access the range.
+    pub stage: ShaderStage,
+    /// Start of the range in bytes, must be a multiple of 4.
+    pub offset: u32,
+    /// Size of the range in bytes, must be a multiple of 4.
+    pub size: u32,
+}
+
+/// How the output of the fragment shader is combined with a color attachment.
+///
+/// With blending enabled, the written color is `src_color_factor * src <color_op>
+/// dst_color_factor * dst`, and the same for the alpha component, where `src` is the output of
+/// the fragment shader and `dst` the current value of the attachment.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BlendState {
+    /// Whether to blend the output with the attachment, if disabled the output is written as is.
+    pub blend_enable: bool,
+    /// Factor applied to the color output of the fragment shader.
+    pub src_color_factor: BlendFactor,
+    /// Factor applied to the color of the attachment.
+    pub dst_color_factor: BlendFactor,
+    /// Operation combining the source and destination colors.
+    pub color_op: BlendOp,
+    /// Factor applied to the alpha output of the fragment shader.
+    pub src_alpha_factor: BlendFactor,
+    /// Factor applied to the alpha of the attachment.
+    pub dst_alpha_factor: BlendFactor,
+    /// Operation combining the source and destination alpha.
+    pub alpha_op: BlendOp,
+    /// Components of the attachment that are written.
+    pub write_mask: ColorComponentFlags,
+}
+
+impl Default for BlendState {
+    fn default() -> Self {
+        Self::opaque()
+    }
+}
+
+impl BlendState {
+    /// Overwrites the attachment with the output of the fragment shader.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let blend = plate::BlendState::opaque();
+    /// assert!(!blend.blend_enable);
+    /// ```
+    pub fn opaque() -> Self {
+        Self {
+            blend_enable: false,
+            src_color_factor: BlendFactor::ONE,
+            dst_color_factor: BlendFactor::ZERO,
+            color_op: BlendOp::ADD,
+            src_alpha_factor: BlendFactor::ONE,
+            dst_alpha_factor: BlendFactor::ZERO,
+            alpha_op: BlendOp::ADD,
+            write_mask: ColorComponentFlags::RGBA,
+        }
+    }
+
+    /// Blends the output over the attachment using the output alpha, for transparent geometry.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let blend = plate::BlendState::alpha_blend();
+    /// assert_eq!(blend.dst_color_factor, plate::BlendFactor::ONE_MINUS_SRC_ALPHA);
+    /// ```
+    pub fn alpha_blend() -> Self {
+        Self {
+            blend_enable: true,
+            src_color_factor: BlendFactor::SRC_ALPHA,
+            dst_color_factor: BlendFactor::ONE_MINUS_SRC_ALPHA,
+            src_alpha_factor: BlendFactor::ONE,
+            dst_alpha_factor: BlendFactor::ONE_MINUS_SRC_ALPHA,
+            ..Self::opaque()
+        }
+    }
+
+    /// Adds the output, weighted by its alpha, to the attachment, for effects such as particles
+    /// or light accumulation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let blend = plate::BlendState::additive();
+    /// assert_eq!(blend.dst_color_factor, plate::BlendFactor::ONE);
+    /// ```
+    pub fn additive() -> Self {
+        Self {
+            blend_enable: true,
+            src_color_factor: BlendFactor::SRC_ALPHA,
+            dst_color_factor: BlendFactor::ONE,
+            src_alpha_factor: BlendFactor::ONE,
+            dst_alpha_factor: BlendFactor::ONE,
+            ..Self::opaque()
+        }
+    }
+
+    fn attachment_state(&self) -> vk::PipelineColorBlendAttachmentState {
+        *vk::PipelineColorBlendAttachmentState::builder()
+            .blend_enable(self.blend_enable)
+            .src_color_blend_factor(self.src_color_factor)
+            .dst_color_blend_factor(self.dst_color_factor)
+            .color_blend_op(self.color_op)
+            .src_alpha_blend_factor(self.src_alpha_factor)
+            .dst_alpha_blend_factor(self.dst_alpha_factor)
+            .alpha_blend_op(self.alpha_op)
+            .color_write_mask(self.write_mask)
+    }
+}
+
+/// Values for the specialization constants of a shader stage.
+///
+/// Specialization constants are declared in GLSL with
+/// `layout(constant_id = 0) const uint VALUE = 1;` and take the value set here when the
+/// [`Pipeline`] is created, so pipelines differing only by a constant can share the same shader
+/// code. Constants not set keep the default value from the shader.
+#[derive(Clone, Debug, Default)]
+pub struct Specialization {
+    entries: Vec<vk::SpecializationMapEntry>,
+    data: Vec<u8>,
+}
+
+impl Specialization {
+    /// Creates a Specialization without any constants.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let specialization = plate::Specialization::new()
+    ///     .set_u32(0, 4)
+    ///     .set_f32(1, 0.5);
+    /// ```
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the `uint` constant with `constant_id`.
+    pub fn set_u32(self, constant_id: u32, value: u32) -> Self {
+        self.set(constant_id, &value.to_ne_bytes())
+    }
+
+    /// Sets the `int` constant with `constant_id`.
+    pub fn set_i32(self, constant_id: u32, value: i32) -> Self {
+        self.set(constant_id, &value.to_ne_bytes())
+    }
+
+    /// Sets the `float` constant with `constant_id`.
+    pub fn set_f32(self, constant_id: u32, value: f32) -> Self {
+        self.set(constant_id, &value.to_ne_bytes())
+    }
+
+    /// Sets the `bool` constant with `constant_id`.
+    pub fn set_bool(self, constant_id: u32, value: bool) -> Self {
+        self.set_u32(constant_id, value as vk::Bool32)
+    }
+
+    fn set(mut self, constant_id: u32, bytes: &[u8]) -> Self {
+        match self.entries.iter().find(|e| e.constant_id == constant_id) {
+            Some(entry) => {
+                let offset = entry.offset as usize;
+                self.data[offset..offset + entry.size].copy_from_slice(bytes);
+            },
+            None => {
+                self.entries.push(vk::SpecializationMapEntry {
+                    constant_id,
+                    offset: self.data.len() as u32,
+                    size: bytes.len(),
+                });
+                self.data.extend_from_slice(bytes);
+            },
+        }
+        self
+    }
+
+    fn info(&self) -> vk::SpecializationInfo {
+        *vk::SpecializationInfo::builder()
+            .map_entries(&self.entries)
+            .data(&self.data)
+    }
+}
+
 /// Aditional parameters for [`Pipeline`] creation.
 pub struct PipelineParameters<'a> {
     /// BindingDescriptions of the vertex to be used by the pipeline.
@@ -87,10 +357,51 @@ pub struct PipelineParameters<'a> {
     pub vertex_attribute_descriptions: Vec<VertexAttributeDescription>,
     /// DescriptorSetLayouts to be used by the pipeline.
     pub descriptor_set_layouts: &'a [&'a DescriptorSetLayout],
+    /// Push constant ranges to be used by the pipeline.
+    pub push_constant_ranges: &'a [PushConstantRange],
     /// Direction of the vertices to consider front-facing.
     pub front_face: FrontFace,
     /// The orientation of triangles to cull.
     pub cull_mode: CullModeFlags,
+    /// How polygons are rasterized, as filled triangles, lines or points.
+    ///
+    /// Modes other than [`FILL`](PolygonMode::FILL) require the
+    /// [`FILL_MODE_NON_SOLID`](DeviceFeatures::FILL_MODE_NON_SOLID) feature.
+    pub polygon_mode: PolygonMode,
+    /// Width in pixels of rasterized lines.
+    ///
+    /// Widths other than 1.0 require the [`WIDE_LINES`](DeviceFeatures::WIDE_LINES) feature.
+    pub line_width: f32,
+    /// Whether to compare fragments against the depth attachment.
+    pub depth_test: bool,
+    /// Whether to write the depth of passing fragments to the depth attachment.
+    pub depth_write: bool,
+    /// Comparison used by the depth test.
+    pub depth_compare_op: CompareOp,
+    /// Index of the [`RenderPass`] subpass in which the pipeline will be used.
+    pub subpass: u32,
+    /// Whether the viewport and scissor are dynamic state, set when recording the command buffer.
+    ///
+    /// When enabled the pipeline does not need to be recreated when the render target is resized.
+    pub dynamic_viewport: bool,
+    /// Extent of the viewport and scissor baked into the pipeline when `dynamic_viewport` is
+    /// disabled, in which case it must not be zero.
+    pub extent: (u32, u32),
+    /// Number of samples per pixel to rasterize, must match the samples of the subpass
+    /// attachments.
+    pub rasterization_samples: SampleCount,
+    /// [`BlendState`] of each color attachment of the subpass. A single BlendState is used for
+    /// every attachment, and no BlendStates is the same as [`BlendState::opaque()`].
+    ///
+    /// Different BlendStates for each attachment require the
+    /// [`INDEPENDENT_BLEND`](DeviceFeatures::INDEPENDENT_BLEND) feature.
+    pub blend_states: &'a [BlendState],
+    /// Specialization constants of the vertex shader.
+    pub vertex_specialization: Option<&'a Specialization>,
+    /// Specialization constants of the fragment shader.
+    pub fragment_specialization: Option<&'a Specialization>,
+    /// [`PipelineCache`] to reuse the results of previous pipeline creations from.
+    pub pipeline_cache: Option<&'a PipelineCache>,
 }
 
 impl<'a> Default for PipelineParameters<'_> {
@@ -99,8 +410,22 @@ impl<'a> Default for PipelineParameters<'_> {
             vertex_binding_descriptions: vec![],
             vertex_attribute_descriptions: vec![],
             descriptor_set_layouts: &[],
+            push_constant_ranges: &[],
             front_face: FrontFace::COUNTER_CLOCKWISE,
             cull_mode: CullModeFlags::NONE,
+            polygon_mode: PolygonMode::FILL,
+            line_width: 1.0,
+            depth_test: true,
+            depth_write: true,
+            depth_compare_op: CompareOp::LESS,
+            subpass: 0,
+            dynamic_viewport: true,
+            extent: (0, 0),
+            rasterization_samples: SampleCount::Sample1,
+            blend_states: &[],
+            vertex_specialization: None,
+            fragment_specialization: None,
+            pipeline_cache: None,
         }
     }
 }
@@ -113,27 +438,33 @@ pub struct Pipeline {
     device: Arc<Device>,
     pipeline: vk::Pipeline,
     pub(crate) layout: vk::PipelineLayout,
-    vert_shader: vk::ShaderModule,
-    frag_shader: vk::ShaderModule,
+    push_constant_ranges: Vec<PushConstantRange>,
+    dynamic_viewport: bool,
+    /// Mask of the vertex input bindings the Pipeline reads from.
+    vertex_bindings: u64,
 }
 
 impl Drop for Pipeline {
     fn drop(&mut self) {
         unsafe {
-            self.device.destroy_shader_module(self.vert_shader, None);
-            self.device.destroy_shader_module(self.frag_shader, None);
-
             self.device.destroy_pipeline(self.pipeline, None);
             self.device.destroy_pipeline_layout(self.layout, None);
         }
     }
 }
 
+impl DebugObject for Pipeline {
+    fn debug_handle(&self) -> (ObjectType, u64) {
+        (ObjectType::PIPELINE, vk::Handle::as_raw(self.pipeline))
+    }
+}
+
 impl Pipeline {
     /// Creates a Pipeline.
     ///
     /// The vertex input data in the shaders must match the binding and attribute descriptions
-    /// specified in `params`.
+    /// specified in `params`. The pipeline can only be used in the `subpass` of `render_pass`
+    /// specified in `params`, or in a compatible render pass.
     ///
     /// # Examples
     ///
@@ -159,31 +490,144 @@ impl Pipeline {
         frag_code: &[u32],
         params: &PipelineParameters,
     ) -> Result<Self, Error> {
+        let vert_shader = ShaderModule::new(device, vert_code)?;
+        let frag_shader = ShaderModule::new(device, frag_code)?;
+        Self::from_modules(device, render_pass, &vert_shader, &frag_shader, params)
+    }
+
+    /// Creates a Pipeline with shaders loaded from SPIR-V binary files.
+    ///
+    /// Returns a [`ShaderError`](crate::ShaderError) if a file can not be read or is not valid
+    /// SPIR-V.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # let event_loop = winit::event_loop::EventLoop::new();
+    /// # let window = winit::window::WindowBuilder::new().build(&event_loop)?;
+    /// # let device = plate::Device::new(&Default::default(), &Default::default(), Some(&window))?;
+    /// # let render_pass = plate::RenderPass::new(&device, &[], &[], &[])?;
+    /// let pipeline = plate::pipeline::Pipeline::from_spirv_files(
+    ///     &device,
+    ///     &render_pass,
+    ///     "shaders/shader.vert.spv",
+    ///     "shaders/shader.frag.spv",
+    ///     &Default::default(),
+    /// )?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn from_spirv_files<P: AsRef<Path>>(
+        device: &Arc<Device>,
+        render_pass: &RenderPass,
+        vert_path: P,
+        frag_path: P,
+        params: &PipelineParameters,
+    ) -> Result<Self, Error> {
+        let vert_shader = ShaderModule::from_file(device, vert_path)?;
+        let frag_shader = ShaderModule::from_file(device, frag_path)?;
+        Self::from_modules(device, render_pass, &vert_shader, &frag_shader, params)
+    }
+
+    /// Creates a Pipeline from existing [`ShaderModules`](ShaderModule).
+    ///
+    /// The modules are only used during creation and can be dropped or reused for other
+    /// pipelines afterwards.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # let event_loop = winit::event_loop::EventLoop::new();
+    /// # let window = winit::window::WindowBuilder::new().build(&event_loop)?;
+    /// # let device = plate::Device::new(&Default::default(), &Default::default(), Some(&window))?;
+    /// # let render_pass = plate::RenderPass::new(&device, &[], &[], &[])?;
+    /// # let (vert_code, frag_code) = ([0], [0]);
+    /// let vert_shader = plate::ShaderModule::new(&device, &vert_code)?;
+    /// let frag_shader = plate::ShaderModule::new(&device, &frag_code)?;
+    /// let pipeline = plate::pipeline::Pipeline::from_modules(
+    ///     &device,
+    ///     &render_pass,
+    ///     &vert_shader,
+    ///     &frag_shader,
+    ///     &Default::default(),
+    /// )?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn from_modules(
+        device: &Arc<Device>,
+        render_pass: &RenderPass,
+        vert_shader: &ShaderModule,
+        frag_shader: &ShaderModule,
+        params: &PipelineParameters,
+    ) -> Result<Self, Error> {
+        let color_attachment_count = *render_pass.attachment_counts.get(params.subpass as usize)
+            .ok_or(PipelineError::SubpassOutOfBounds { subpass: params.subpass, count: render_pass.attachment_counts.len() })?;
+        let subpass_samples = render_pass.samples[params.subpass as usize];
+        if params.rasterization_samples != subpass_samples {
+            return Err(PipelineError::SampleCountMismatch { pipeline: params.rasterization_samples, render_pass: subpass_samples }.into())
+        }
+        if !params.dynamic_viewport && (params.extent.0 == 0 || params.extent.1 == 0) {
+            return Err(PipelineError::ZeroExtent(params.extent).into())
+        }
+
         let binding_descriptions: Vec<_> = params.vertex_binding_descriptions.iter().map(|b| b.0).collect();
         let attribute_descriptions: Vec<_> = params.vertex_attribute_descriptions.iter().map(|a| a.0).collect();
 
-        let vert_shader_info = vk::ShaderModuleCreateInfo::builder().code(vert_code);
-        let frag_shader_info = vk::ShaderModuleCreateInfo::builder().code(frag_code);
+        attribute_descriptions.iter()
+            .map(|a| {
+                let props = unsafe { device.instance.get_physical_device_format_properties(device.physical_device, a.format) };
+                match props.buffer_features.contains(vk::FormatFeatureFlags::VERTEX_BUFFER) {
+                    true => Ok(()),
+                    false => Err(PipelineError::UnsupportedVertexFormat(a.format)),
+                }
+            })
+            .collect::<Result<Vec<_>, _>>()?;
 
-        let vert_shader = unsafe {
-            device.create_shader_module(&vert_shader_info, None)?
-        };
-        let frag_shader = unsafe {
-            device.create_shader_module(&frag_shader_info, None)?
+        if params.polygon_mode != PolygonMode::FILL && !device.features.contains(DeviceFeatures::FILL_MODE_NON_SOLID) {
+            return Err(PipelineError::MissingFeature(DeviceFeatures::FILL_MODE_NON_SOLID).into())
+        }
+        if params.line_width != 1.0 {
+            if !device.features.contains(DeviceFeatures::WIDE_LINES) {
+                return Err(PipelineError::MissingFeature(DeviceFeatures::WIDE_LINES).into())
+            }
+            let [min, max] = unsafe { device.instance.get_physical_device_properties(device.physical_device).limits.line_width_range };
+            if !(min..=max).contains(&params.line_width) {
+                return Err(PipelineError::LineWidthOutOfRange { width: params.line_width, min, max }.into())
+            }
+        }
+
+        let color_blend_attachments = match params.blend_states {
+            [] => vec![BlendState::opaque().attachment_state(); color_attachment_count],
+            [blend] => vec![blend.attachment_state(); color_attachment_count],
+            blends if blends.len() == color_attachment_count => {
+                if blends.iter().any(|b| b != &blends[0]) && !device.features.contains(DeviceFeatures::INDEPENDENT_BLEND) {
+                    return Err(PipelineError::MissingFeature(DeviceFeatures::INDEPENDENT_BLEND).into())
+                }
+                blends.iter().map(|b| b.attachment_state()).collect()
+            },
+            blends => return Err(PipelineError::BlendStateCountMismatch { count: blends.len(), attachments: color_attachment_count }.into()),
         };
 
         let name = ffi::CString::new("main").expect("Should never fail to build \"main\" string");
 
-        let stage_infos = [
-            *vk::PipelineShaderStageCreateInfo::builder()
-                .module(vert_shader)
-                .stage(vk::ShaderStageFlags::VERTEX)
-                .name(&name),
-            *vk::PipelineShaderStageCreateInfo::builder()
-                .module(frag_shader)
-                .stage(vk::ShaderStageFlags::FRAGMENT)
-                .name(&name),
-        ];
+        let vert_specialization = params.vertex_specialization.map(|s| s.info());
+        let frag_specialization = params.fragment_specialization.map(|s| s.info());
+
+        let mut vert_stage_info = vk::PipelineShaderStageCreateInfo::builder()
+            .module(vert_shader.module)
+            .stage(vk::ShaderStageFlags::VERTEX)
+            .name(&name);
+        if let Some(info) = &vert_specialization {
+            vert_stage_info = vert_stage_info.specialization_info(info);
+        }
+        let mut frag_stage_info = vk::PipelineShaderStageCreateInfo::builder()
+            .module(frag_shader.module)
+            .stage(vk::ShaderStageFlags::FRAGMENT)
+            .name(&name);
+        if let Some(info) = &frag_specialization {
+            frag_stage_info = frag_stage_info.specialization_info(info);
+        }
+
+        let stage_infos = [*vert_stage_info, *frag_stage_info];
 
         let vertex_info = vk::PipelineVertexInputStateCreateInfo::builder()
             .vertex_binding_descriptions(&binding_descriptions)
@@ -194,8 +638,8 @@ impl Pipeline {
         let viewports = [vk::Viewport {
             x: 0.0,
             y: 0.0,
-            width: 0.0,
-            height: 0.0,
+            width: params.extent.0 as f32,
+            height: params.extent.1 as f32,
             min_depth: 0.0,
             max_depth: 1.0,
         }];
@@ -203,8 +647,8 @@ impl Pipeline {
         let scissors = [vk::Rect2D {
             offset: vk::Offset2D { x: 0, y: 0 },
             extent: vk::Extent2D {
-                width: 0,
-                height: 0,
+                width: params.extent.0,
+                height: params.extent.1,
             },
         }];
 
@@ -213,28 +657,14 @@ impl Pipeline {
             .scissors(&scissors);
 
         let rasterization = vk::PipelineRasterizationStateCreateInfo::builder()
-            .line_width(1.0)
+            .line_width(params.line_width)
             .front_face(params.front_face)
             .cull_mode(params.cull_mode)
-            .polygon_mode(vk::PolygonMode::FILL);
+            .polygon_mode(params.polygon_mode);
 
         let multisampling = vk::PipelineMultisampleStateCreateInfo::builder()
             .sample_shading_enable(false)
-            .rasterization_samples(vk::SampleCountFlags::TYPE_1);
-
-        let color_blend_attachments = (0..render_pass.attachment_counts[0]).into_iter()
-            .map(|_| {
-                *vk::PipelineColorBlendAttachmentState::builder()
-                    .blend_enable(true)
-                    .src_color_blend_factor(vk::BlendFactor::SRC_ALPHA)
-                    .dst_color_blend_factor(vk::BlendFactor::ONE_MINUS_SRC_ALPHA)
-                    .color_blend_op(vk::BlendOp::ADD)
-                    .src_alpha_blend_factor(vk::BlendFactor::SRC_ALPHA)
-                    .dst_alpha_blend_factor(vk::BlendFactor::ONE_MINUS_SRC_ALPHA)
-                    .alpha_blend_op(vk::BlendOp::ADD)
-                    .color_write_mask(vk::ColorComponentFlags::RGBA)
-            })
-            .collect::<Vec<_>>();
+            .rasterization_samples(params.rasterization_samples.flags());
 
         let color_blend =
             vk::PipelineColorBlendStateCreateInfo::builder().attachments(&color_blend_attachments);
@@ -242,18 +672,41 @@ impl Pipeline {
         let layouts = params.descriptor_set_layouts.into_iter()
             .map(|l| l.layout)
             .collect::<Vec<_>>();
-        let layout_info = vk::PipelineLayoutCreateInfo::builder().set_layouts(&layouts);
+
+        let max_push_constants_size = unsafe { device.instance.get_physical_device_properties(device.physical_device).limits.max_push_constants_size };
+        let push_constant_ranges = params.push_constant_ranges.iter()
+            .map(|r| {
+                if r.offset & 3 != 0 || r.size & 3 != 0 {
+                    return Err(PipelineError::MisalignedPushConstants { offset: r.offset, size: r.size })
+                }
+                match r.offset.checked_add(r.size) {
+                    Some(end) if end <= max_push_constants_size => Ok(*vk::PushConstantRange::builder()
+                        .stage_flags(r.stage)
+                        .offset(r.offset)
+                        .size(r.size)),
+                    end => Err(PipelineError::PushConstantRangeTooLarge { end: end.unwrap_or(u32::MAX), max: max_push_constants_size }),
+                }
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let layout_info = vk::PipelineLayoutCreateInfo::builder()
+            .set_layouts(&layouts)
+            .push_constant_ranges(&push_constant_ranges);
         let layout = unsafe {
             device.create_pipeline_layout(&layout_info, None)?
         };
 
+        let dynamic_states = match params.dynamic_viewport {
+            true => vec![vk::DynamicState::VIEWPORT, vk::DynamicState::SCISSOR],
+            false => vec![],
+        };
         let dynamic_state = vk::PipelineDynamicStateCreateInfo::builder()
-            .dynamic_states(&[vk::DynamicState::VIEWPORT, vk::DynamicState::SCISSOR]);
+            .dynamic_states(&dynamic_states);
 
         let stencil_state = vk::PipelineDepthStencilStateCreateInfo::builder()
-            .depth_test_enable(true)
-            .depth_write_enable(true)
-            .depth_compare_op(vk::CompareOp::LESS)
+            .depth_test_enable(params.depth_test)
+            .depth_write_enable(params.depth_write)
+            .depth_compare_op(params.depth_compare_op)
             .depth_bounds_test_enable(false)
             .stencil_test_enable(false);
 
@@ -268,10 +721,11 @@ impl Pipeline {
             .layout(layout)
             .render_pass(render_pass.render_pass)
             .dynamic_state(&dynamic_state)
-            .subpass(0)
+            .subpass(params.subpass)
             .depth_stencil_state(&stencil_state);
 
-        let pipeline = match unsafe { device.create_graphics_pipelines(vk::PipelineCache::null(), &[*pipeline_info], None) } {
+        let cache = params.pipeline_cache.map_or(vk::PipelineCache::null(), |c| c.cache);
+        let pipeline = match unsafe { device.create_graphics_pipelines(cache, &[*pipeline_info], None) } {
             Ok(p) => Ok(p[0]),
             Err((_, e)) => Err(e)
         }?;
@@ -280,14 +734,19 @@ impl Pipeline {
             device: Arc::clone(&device),
             pipeline,
             layout,
-            vert_shader,
-            frag_shader,
+            push_constant_ranges: params.push_constant_ranges.to_vec(),
+            dynamic_viewport: params.dynamic_viewport,
+            vertex_bindings: binding_descriptions.iter()
+                .fold(0, |mask, b| mask | 1u64.checked_shl(b.binding).unwrap_or(0)),
         })
     }
 
     /// Binds the Pipeline.
     ///
-    /// To be used when recording a command buffer.
+    /// To be used when recording a command buffer. If the pipeline was created with
+    /// `dynamic_viewport`, the viewport and scissor are set to cover `extent`, they can be
+    /// changed afterwards with [`CommandBuffer::set_viewport()`] and
+    /// [`CommandBuffer::set_scissor()`]. Otherwise `extent` is ignored.
     ///
     /// # Examples
     /// 
@@ -298,7 +757,7 @@ impl Pipeline {
     /// # let device = plate::Device::new(&Default::default(), &Default::default(), Some(&window))?;
     /// # let cmd_pool = plate::CommandPool::new(&device)?;
     /// # let cmd_buffer = cmd_pool.alloc_cmd_buffer(plate::CommandBufferLevel::PRIMARY)?;
-    /// # let mut swapchain = plate::swapchain::Swapchain::new(&device, &window)?;
+    /// # let mut swapchain = plate::swapchain::Swapchain::new(&device, &window, &Default::default())?;
     /// # let render_pass = plate::RenderPass::new(&device, &[], &[], &[])?;
     /// # let pipeline = plate::pipeline::Pipeline::new(&device, &render_pass, &[], &[],
     /// # &Default::default())?;
@@ -311,21 +770,238 @@ impl Pipeline {
         unsafe {
             self.device.cmd_bind_pipeline(**command_buffer, vk::PipelineBindPoint::GRAPHICS, self.pipeline)
         }
+        command_buffer.track_pipeline(self.vertex_bindings);
 
-        let viewports = [vk::Viewport {
-            x: 0.0,
-            y: 0.0,
-            width: extent.0 as f32,
-            height: extent.1 as f32,
-            min_depth: 0.0,
-            max_depth: 1.0,
-        }];
-        unsafe { self.device.cmd_set_viewport(**command_buffer, 0, &viewports) };
+        if self.dynamic_viewport {
+            command_buffer.set_viewport(0.0, 0.0, extent.0 as f32, extent.1 as f32, 0.0, 1.0);
+            command_buffer.set_scissor(0, 0, extent.0, extent.1);
+        }
+    }
 
-        let scissors = [vk::Rect2D {
-            offset: vk::Offset2D { x: 0, y: 0 },
-            extent: vk::Extent2D { width: extent.0, height: extent.1 },
-        }];
-        unsafe { self.device.cmd_set_scissor(**command_buffer, 0, &scissors) };
+    /// Updates the push constants of the given stages with `data`, starting at `offset` bytes.
+    ///
+    /// To be used when recording a command buffer. The data must be contained in one of the
+    /// [`PushConstantRanges`](PushConstantRange) the Pipeline was created with for all the
+    /// provided stages, `stage` must include every stage of the ranges the data overlaps, and
+    /// `offset` and the size of `T` must be multiples of 4, otherwise an error is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # let event_loop = winit::event_loop::EventLoop::new();
+    /// # let window = winit::window::WindowBuilder::new().build(&event_loop)?;
+    /// # let device = plate::Device::new(&Default::default(), &Default::default(), Some(&window))?;
+    /// # let cmd_pool = plate::CommandPool::new(&device)?;
+    /// # let cmd_buffer = cmd_pool.alloc_cmd_buffer(plate::CommandBufferLevel::PRIMARY)?;
+    /// # let render_pass = plate::RenderPass::new(&device, &[], &[], &[])?;
+    /// # let pipeline = plate::pipeline::Pipeline::new(&device, &render_pass, &[], &[],
+    /// # &Default::default())?;
+    /// let model = [1.0f32; 16];
+    /// // cmd_buffer.record(.., || {
+    ///     // pipeline.bind(..);
+    ///     pipeline.push_constants(&cmd_buffer, plate::ShaderStage::VERTEX, 0, &model)?;
+    /// // })?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn push_constants<T>(&self, command_buffer: &CommandBuffer, stage: ShaderStage, offset: u32, data: &T) -> Result<(), Error> {
+        let size = mem::size_of::<T>() as u32;
+        if offset & 3 != 0 || size & 3 != 0 {
+            return Err(PipelineError::MisalignedPushConstants { offset, size }.into())
+        }
+
+        let end = offset.checked_add(size)
+            .ok_or(PipelineError::PushConstantOutOfRange { stage, offset, end: u32::MAX })?;
+        let in_range = self.push_constant_ranges.iter()
+            .any(|r| r.stage.contains(stage) && r.offset <= offset && end <= r.offset + r.size);
+        if stage.is_empty() || !in_range {
+            return Err(PipelineError::PushConstantOutOfRange { stage, offset, end }.into())
+        }
+        let overlapping = self.push_constant_ranges.iter()
+            .find(|r| r.offset < end && offset < r.offset + r.size && !stage.contains(r.stage));
+        if let Some(r) = overlapping {
+            return Err(PipelineError::PushConstantStageMismatch { stage, range_stage: r.stage }.into())
+        }
+
+        // Passed as a raw pointer, the padding bytes of T must not be read as a &[u8]
+        unsafe {
+            (self.device.fp_v1_0().cmd_push_constants)(
+                **command_buffer,
+                self.layout,
+                stage,
+                offset,
+                size,
+                data as *const T as *const std::ffi::c_void,
+            )
+        };
+
+        Ok(())
+    }
+}
+
+/// Stores the results of pipeline creation to speed up the creation of similar pipelines.
+///
+/// Pass the PipelineCache in the [`PipelineParameters`] of the pipelines to create. The contents
+/// of the cache can be retrieved with [`get_data()`](Self::get_data()) and stored on disk, to be
+/// loaded with [`from_data()`](Self::from_data()) in later runs of the application.
+pub struct PipelineCache {
+    device: Arc<Device>,
+    cache: vk::PipelineCache,
+}
+
+impl Drop for PipelineCache {
+    fn drop(&mut self) {
+        unsafe { self.device.destroy_pipeline_cache(self.cache, None) };
+    }
+}
+
+impl PipelineCache {
+    /// Creates an empty PipelineCache.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # let event_loop = winit::event_loop::EventLoop::new();
+    /// # let window = winit::window::WindowBuilder::new().build(&event_loop)?;
+    /// # let device = plate::Device::new(&Default::default(), &Default::default(), Some(&window))?;
+    /// let cache = plate::PipelineCache::new(&device)?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn new(device: &Arc<Device>) -> Result<Self, Error> {
+        Self::from_data(device, &[])
+    }
+
+    /// Creates a PipelineCache with the data retrieved from a previous cache.
+    ///
+    /// If the data was not created by the same driver and physical device, the cache starts
+    /// empty instead.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # let event_loop = winit::event_loop::EventLoop::new();
+    /// # let window = winit::window::WindowBuilder::new().build(&event_loop)?;
+    /// # let device = plate::Device::new(&Default::default(), &Default::default(), Some(&window))?;
+    /// let data = std::fs::read("pipeline_cache.bin").unwrap_or_default();
+    /// let cache = plate::PipelineCache::from_data(&device, &data)?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn from_data(device: &Arc<Device>, data: &[u8]) -> Result<Self, Error> {
+        let properties = unsafe { device.instance.get_physical_device_properties(device.physical_device) };
+        let data = match cache_header_matches(data, &properties) {
+            true => data,
+            false => &[],
+        };
+
+        let info = vk::PipelineCacheCreateInfo::builder().initial_data(data);
+        let cache = unsafe { device.create_pipeline_cache(&info, None)? };
+
+        Ok(Self {
+            device: Arc::clone(device),
+            cache,
+        })
+    }
+
+    /// Returns the contents of the PipelineCache, to be loaded with
+    /// [`from_data()`](Self::from_data()).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # let event_loop = winit::event_loop::EventLoop::new();
+    /// # let window = winit::window::WindowBuilder::new().build(&event_loop)?;
+    /// # let device = plate::Device::new(&Default::default(), &Default::default(), Some(&window))?;
+    /// # let cache = plate::PipelineCache::new(&device)?;
+    /// let data = cache.get_data()?;
+    /// std::fs::write("pipeline_cache.bin", data)?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn get_data(&self) -> Result<Vec<u8>, Error> {
+        Ok(unsafe { self.device.get_pipeline_cache_data(self.cache)? })
+    }
+}
+
+/// Checks that `data` starts with a version one pipeline cache header created by the physical
+/// device with the given properties.
+fn cache_header_matches(data: &[u8], properties: &vk::PhysicalDeviceProperties) -> bool {
+    const HEADER_SIZE: usize = 16 + vk::UUID_SIZE;
+    if data.len() < HEADER_SIZE {
+        return false
+    }
+
+    let read_u32 = |i: usize| u32::from_ne_bytes([data[i], data[i+1], data[i+2], data[i+3]]);
+    read_u32(0) as usize >= HEADER_SIZE
+        && read_u32(4) == vk::PipelineCacheHeaderVersion::ONE.as_raw() as u32
+        && read_u32(8) == properties.vendor_id
+        && read_u32(12) == properties.device_id
+        && data[16..HEADER_SIZE] == properties.pipeline_cache_uuid
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_specialization_packing() {
+        let specialization = Specialization::new()
+            .set_u32(0, 4)
+            .set_f32(3, 0.5)
+            .set_bool(1, true)
+            .set_u32(0, 8);
+
+        let ids = specialization.entries.iter().map(|e| (e.constant_id, e.offset, e.size)).collect::<Vec<_>>();
+        assert_eq!(ids, [(0, 0, 4), (3, 4, 4), (1, 8, 4)]);
+        assert_eq!(specialization.data[0..4], 8u32.to_ne_bytes());
+        assert_eq!(specialization.data[4..8], 0.5f32.to_ne_bytes());
+        assert_eq!(specialization.data[8..12], 1u32.to_ne_bytes());
+    }
+
+    #[test]
+    fn test_cache_header_matches() {
+        let properties = vk::PhysicalDeviceProperties {
+            vendor_id: 0x10de,
+            device_id: 0x1234,
+            pipeline_cache_uuid: [7; vk::UUID_SIZE],
+            ..Default::default()
+        };
+
+        let mut data = vec![];
+        data.extend_from_slice(&32u32.to_ne_bytes());
+        data.extend_from_slice(&1u32.to_ne_bytes());
+        data.extend_from_slice(&0x10deu32.to_ne_bytes());
+        data.extend_from_slice(&0x1234u32.to_ne_bytes());
+        data.extend_from_slice(&[7; vk::UUID_SIZE]);
+        data.extend_from_slice(&[0; 8]);
+        assert!(cache_header_matches(&data, &properties));
+
+        let mut other_device = data.clone();
+        other_device[12] ^= 1;
+        assert!(!cache_header_matches(&other_device, &properties));
+
+        let mut other_uuid = data.clone();
+        other_uuid[20] = 0;
+        assert!(!cache_header_matches(&other_uuid, &properties));
+
+        assert!(!cache_header_matches(&data[..16], &properties));
+        assert!(!cache_header_matches(&[], &properties));
+    }
+
+    #[repr(C)]
+    #[allow(dead_code)]
+    struct PackedVertex {
+        pos: [f32; 2],
+        color: [u8; 4],
+    }
+
+    #[test]
+    fn test_packed_attribute_description() {
+        let vertex = PackedVertex { pos: [0.0; 2], color: [255, 0, 0, 255] };
+        let offset = (&vertex.color as *const _ as usize - &vertex as *const _ as usize) as u32;
+
+        let attribute = VertexAttributeDescription::new(0, 1, offset, Format::R8G8B8A8_UNORM);
+        assert_eq!(attribute.0.offset, 8);
+        assert_eq!(attribute.0.format, Format::R8G8B8A8_UNORM);
+
+        let binding = VertexBindingDescription::new(0, std::mem::size_of::<PackedVertex>() as u32, InputRate::VERTEX);
+        assert_eq!(binding.0.stride, 12);
     }
 }
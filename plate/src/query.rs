@@ -0,0 +1,127 @@
+use std::sync::Arc;
+
+use ash::vk;
+
+use crate::{Device, Error};
+
+/// Errors from the query module.
+#[derive(thiserror::Error, Debug)]
+pub enum QueryError {
+    /// The queue family of the device can not write timestamps.
+    #[error("The device queue does not support timestamps")]
+    TimestampsUnsupported,
+}
+
+/// A pool of GPU timestamps, used to measure how long commands take to execute.
+///
+/// Timestamps are written with [`CommandBuffer::write_timestamp()`](crate::CommandBuffer::write_timestamp())
+/// and read back with [`results()`](Self::results()). The pool must be reset with
+/// [`CommandBuffer::reset_query_pool()`](crate::CommandBuffer::reset_query_pool()) before writing
+/// the timestamps again.
+pub struct QueryPool {
+    device: Arc<Device>,
+    pub(crate) pool: vk::QueryPool,
+    count: u32,
+    valid_bits: u32,
+    timestamp_period: f32,
+}
+
+impl Drop for QueryPool {
+    fn drop(&mut self) {
+        unsafe { self.device.destroy_query_pool(self.pool, None) };
+    }
+}
+
+impl QueryPool {
+    /// Creates a QueryPool with space for `count` timestamps.
+    ///
+    /// Returns [`QueryError::TimestampsUnsupported`] if the device queue can not write
+    /// timestamps.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # let event_loop = winit::event_loop::EventLoop::new();
+    /// # let window = winit::window::WindowBuilder::new().build(&event_loop)?;
+    /// # let device = plate::Device::new(&Default::default(), &Default::default(), Some(&window))?;
+    /// let query_pool = plate::QueryPool::new(&device, 2)?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn new(device: &Arc<Device>, count: u32) -> Result<Self, Error> {
+        let limits = unsafe { device.instance.get_physical_device_properties(device.physical_device).limits };
+        let queue_families = unsafe { device.instance.get_physical_device_queue_family_properties(device.physical_device) };
+        let valid_bits = queue_families[device.queue.family as usize].timestamp_valid_bits;
+
+        if valid_bits == 0 || limits.timestamp_period == 0.0 {
+            return Err(QueryError::TimestampsUnsupported.into())
+        }
+
+        let info = vk::QueryPoolCreateInfo::builder()
+            .query_type(vk::QueryType::TIMESTAMP)
+            .query_count(count);
+        let pool = unsafe { device.create_query_pool(&info, None)? };
+
+        Ok(Self {
+            device: Arc::clone(device),
+            pool,
+            count,
+            valid_bits,
+            timestamp_period: limits.timestamp_period,
+        })
+    }
+
+    /// Returns the number of timestamps in the QueryPool.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # let event_loop = winit::event_loop::EventLoop::new();
+    /// # let window = winit::window::WindowBuilder::new().build(&event_loop)?;
+    /// # let device = plate::Device::new(&Default::default(), &Default::default(), Some(&window))?;
+    /// # let query_pool = plate::QueryPool::new(&device, 2)?;
+    /// assert_eq!(query_pool.count(), 2);
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn count(&self) -> u32 {
+        self.count
+    }
+
+    /// Returns every timestamp of the QueryPool in nanoseconds.
+    ///
+    /// Blocks until all the timestamps are written, so it should only be called after the
+    /// command buffer writing them is submitted. Only the difference between two timestamps is
+    /// meaningful.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # let event_loop = winit::event_loop::EventLoop::new();
+    /// # let window = winit::window::WindowBuilder::new().build(&event_loop)?;
+    /// # let device = plate::Device::new(&Default::default(), &Default::default(), Some(&window))?;
+    /// # let query_pool = plate::QueryPool::new(&device, 2)?;
+    /// let timestamps = query_pool.results()?;
+    /// let elapsed_ns = timestamps[1] - timestamps[0];
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn results(&self) -> Result<Vec<u64>, Error> {
+        let mut ticks = vec![0u64; self.count as usize];
+        unsafe {
+            self.device.get_query_pool_results(
+                self.pool,
+                0,
+                self.count,
+                &mut ticks,
+                vk::QueryResultFlags::TYPE_64 | vk::QueryResultFlags::WAIT,
+            )?
+        };
+
+        let mask = match self.valid_bits {
+            64.. => u64::MAX,
+            bits => (1 << bits) - 1,
+        };
+
+        Ok(ticks.into_iter()
+            .map(|t| ((t & mask) as f64 * self.timestamp_period as f64) as u64)
+            .collect())
+    }
+}
@@ -2,12 +2,13 @@ use std::sync::Arc;
 
 use ash::vk;
 
-use crate::{Device, Error, Format, Image, CommandBuffer, PipelineStage};
+use crate::{Device, Error, Format, Image, CommandBuffer, PipelineStage, SampleCount};
 
 pub use vk::AttachmentLoadOp;
 pub use vk::AttachmentStoreOp;
 pub use vk::ImageLayout;
 pub use vk::AccessFlags;
+pub use vk::SubpassContents;
 
 /// Describes [`RenderPass`] Attachment.
 pub struct Attachment {
@@ -21,6 +22,11 @@ pub struct Attachment {
     pub initial_layout: ImageLayout,
     /// Layout of the image when the render pass ends.
     pub final_layout: ImageLayout,
+    /// Number of samples of the image.
+    ///
+    /// Multisampled color attachments must be resolved to single sampled attachments through the
+    /// subpass `resolve_attachments` to be presented.
+    pub samples: SampleCount,
 }
 
 /// Describes an [`Attachment`] reference.
@@ -87,7 +93,9 @@ pub struct RenderPass {
     device: Arc<Device>,
     pub(crate) render_pass: vk::RenderPass,
     pub(crate) attachment_counts: Vec<usize>,
+    pub(crate) samples: Vec<SampleCount>,
     clear_values: Vec<vk::ClearValue>,
+    depth_formats: Vec<bool>,
 }
 
 impl Drop for RenderPass {
@@ -117,25 +125,13 @@ impl RenderPass {
     /// # Ok::<(), Box<dyn std::error::Error>>(())
     /// ```
     pub fn new(device: &Arc<Device>, attachments: &[Attachment], subpasses: &[SubpassDescription], dependencies: &[SubpassDependency]) -> Result<Self, Error> {
-        let clear_values = attachments.iter()
-            .map(|a| {
-                match a.format {
-                    Format::D16_UNORM | Format::D32_SFLOAT | Format::D16_UNORM_S8_UINT | Format::D24_UNORM_S8_UINT | Format::D32_SFLOAT_S8_UINT => {
-                        vk::ClearValue {
-                            depth_stencil: vk::ClearDepthStencilValue {
-                                depth: 1.0,
-                                stencil: 0,
-                            }
-                        }
-                    }
-                    _ => {
-                        vk::ClearValue {
-                            color: vk::ClearColorValue {
-                                float32: [0.0, 0.0, 0.0, 1.0],
-                            }
-                        }
-                    }
-                }
+        let depth_formats = attachments.iter()
+            .map(|a| is_depth_format(a.format))
+            .collect::<Vec<_>>();
+        let clear_values = depth_formats.iter()
+            .map(|depth| match depth {
+                true => clear_depth_stencil(1.0, 0),
+                false => clear_color([0.0, 0.0, 0.0, 1.0]),
             })
             .collect::<Vec<_>>();
 
@@ -143,7 +139,7 @@ impl RenderPass {
             .map(|a| {
                 *vk::AttachmentDescription::builder()
                     .format(a.format)
-                    .samples(vk::SampleCountFlags::TYPE_1)
+                    .samples(a.samples.flags())
                     .load_op(a.load_op)
                     .store_op(a.store_op)
                     .stencil_load_op(vk::AttachmentLoadOp::DONT_CARE)
@@ -230,16 +226,75 @@ impl RenderPass {
         let attachment_counts = subpasses.iter()
             .map(|s| s.color_attachments.len())
             .collect();
+        let samples = subpasses.iter()
+            .map(|s| {
+                s.color_attachments.iter()
+                    .chain(s.depth_attachment.iter())
+                    .find_map(|r| attachments.get(r.attachment as usize))
+                    .map(|a| a.samples)
+                    .unwrap_or(SampleCount::Sample1)
+            })
+            .collect();
         Ok(Self {
             device: Arc::clone(device),
             render_pass,
             attachment_counts,
+            samples,
             clear_values,
+            depth_formats,
         })
     }
 
+    /// Sets the color the color attachments are cleared to when the RenderPass begins.
+    ///
+    /// Only affects attachments with the [`CLEAR`](AttachmentLoadOp::CLEAR) load op. The color
+    /// persists across frames and defaults to opaque black.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # let event_loop = winit::event_loop::EventLoop::new();
+    /// # let window = winit::window::WindowBuilder::new().build(&event_loop)?;
+    /// # let device = plate::Device::new(&Default::default(), &Default::default(), Some(&window))?;
+    /// let mut render_pass = plate::RenderPass::new(&device, &[], &[], &[])?;
+    /// render_pass.set_clear_color([0.1, 0.2, 0.3, 1.0]);
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn set_clear_color(&mut self, color: [f32; 4]) {
+        self.depth_formats.iter()
+            .zip(self.clear_values.iter_mut())
+            .filter(|(depth, _)| !**depth)
+            .for_each(|(_, value)| *value = clear_color(color));
+    }
+
+    /// Sets the values the depth attachments are cleared to when the RenderPass begins.
+    ///
+    /// Only affects attachments with the [`CLEAR`](AttachmentLoadOp::CLEAR) load op. The values
+    /// persist across frames and default to a depth of 1.0 and a stencil of 0.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # let event_loop = winit::event_loop::EventLoop::new();
+    /// # let window = winit::window::WindowBuilder::new().build(&event_loop)?;
+    /// # let device = plate::Device::new(&Default::default(), &Default::default(), Some(&window))?;
+    /// let mut render_pass = plate::RenderPass::new(&device, &[], &[], &[])?;
+    /// // Reversed depth
+    /// render_pass.set_clear_depth_stencil(0.0, 0);
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn set_clear_depth_stencil(&mut self, depth: f32, stencil: u32) {
+        self.depth_formats.iter()
+            .zip(self.clear_values.iter_mut())
+            .filter(|(depth, _)| **depth)
+            .for_each(|(_, value)| *value = clear_depth_stencil(depth, stencil));
+    }
+
     /// Begins the renderpass.
     ///
+    /// In debug builds, the bindings tracked by the command buffer are reset, see
+    /// [`CommandBuffer`].
+    ///
     /// `framebuffer` must contain the attachments described at render pass creation. To be used
     /// when recording a [`CommandBuffer`].
     ///
@@ -259,6 +314,32 @@ impl RenderPass {
     /// # Ok::<(), Box<dyn std::error::Error>>(())
     /// ```
     pub fn begin(&self, cmd_buffer: &CommandBuffer, framebuffer: &Framebuffer) {
+        self.begin_with_contents(cmd_buffer, framebuffer, SubpassContents::INLINE)
+    }
+
+    /// Begins the renderpass, with the commands of the first subpass provided as specified by
+    /// `contents`.
+    ///
+    /// With [`SECONDARY_COMMAND_BUFFERS`](SubpassContents::SECONDARY_COMMAND_BUFFERS) the subpass
+    /// commands must be recorded in secondary command buffers and executed with
+    /// [`CommandBuffer::execute_commands()`].
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # let event_loop = winit::event_loop::EventLoop::new();
+    /// # let window = winit::window::WindowBuilder::new().build(&event_loop)?;
+    /// # let device = plate::Device::new(&Default::default(), &Default::default(), Some(&window))?;
+    /// # let cmd_pool = plate::CommandPool::new(&device)?;
+    /// # let cmd_buffer = cmd_pool.alloc_cmd_buffer(plate::CommandBufferLevel::PRIMARY)?;
+    /// # let render_pass = plate::RenderPass::new(&device, &[], &[], &[])?;
+    /// # let framebuffer = plate::Framebuffer::new(&device, &render_pass, &[], 0, 0)?;
+    /// // cmd_buffer.record(.., || {
+    ///     render_pass.begin_with_contents(&cmd_buffer, &framebuffer, plate::SubpassContents::SECONDARY_COMMAND_BUFFERS);
+    /// // })?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn begin_with_contents(&self, cmd_buffer: &CommandBuffer, framebuffer: &Framebuffer, contents: SubpassContents) {
         let begin_info = vk::RenderPassBeginInfo::builder()
             .render_pass(self.render_pass)
             .framebuffer(framebuffer.framebuffer)
@@ -272,9 +353,10 @@ impl RenderPass {
             self.device.cmd_begin_render_pass(
                 **cmd_buffer,
                 &begin_info,
-                vk::SubpassContents::INLINE,
+                contents,
             )
         }
+        cmd_buffer.reset_draw_state();
     }
 
     /// Ends the renderpass.
@@ -299,10 +381,34 @@ impl RenderPass {
     }
 }
 
+fn is_depth_format(format: Format) -> bool {
+    matches!(
+        format,
+        Format::D16_UNORM | Format::D32_SFLOAT | Format::D16_UNORM_S8_UINT | Format::D24_UNORM_S8_UINT | Format::D32_SFLOAT_S8_UINT
+    )
+}
+
+fn clear_color(color: [f32; 4]) -> vk::ClearValue {
+    vk::ClearValue {
+        color: vk::ClearColorValue {
+            float32: color,
+        }
+    }
+}
+
+fn clear_depth_stencil(depth: f32, stencil: u32) -> vk::ClearValue {
+    vk::ClearValue {
+        depth_stencil: vk::ClearDepthStencilValue {
+            depth,
+            stencil,
+        }
+    }
+}
+
 /// Reference the attachments used by a [`RenderPass`].
 pub struct Framebuffer {
     device: Arc<Device>,
-    framebuffer: vk::Framebuffer,
+    pub(crate) framebuffer: vk::Framebuffer,
     extent: vk::Extent2D,
 }
 
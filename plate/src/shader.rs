@@ -0,0 +1,149 @@
+use std::{path::Path, sync::Arc};
+
+use ash::vk;
+
+use crate::{Device, Error};
+
+/// Magic number at the start of every SPIR-V module.
+const SPIRV_MAGIC: u32 = 0x07230203;
+
+/// Errors from the shader module.
+#[derive(thiserror::Error, Debug)]
+pub enum ShaderError {
+    /// The shader file could not be read.
+    #[error("Failed to read shader file")]
+    Io(#[from] std::io::Error),
+    /// The code is empty.
+    #[error("SPIR-V code is empty")]
+    Empty,
+    /// The length of the code in bytes is not a multiple of the SPIR-V word size.
+    #[error("SPIR-V code of {0} bytes is not a whole number of 4 byte words")]
+    MisalignedCode(usize),
+    /// The code does not start with the SPIR-V magic number.
+    #[error("Invalid SPIR-V magic number {0:#010x}")]
+    InvalidMagicNumber(u32),
+}
+
+/// Compiled SPIR-V code to be used by a [`Pipeline`](crate::Pipeline).
+///
+/// Shaders can be embedded at compile time, for example with `vk_shader_macros::include_glsl!`,
+/// and created with [`new()`](Self::new()), or loaded at runtime with
+/// [`from_file()`](Self::from_file()).
+pub struct ShaderModule {
+    device: Arc<Device>,
+    pub(crate) module: vk::ShaderModule,
+}
+
+impl Drop for ShaderModule {
+    fn drop(&mut self) {
+        unsafe { self.device.destroy_shader_module(self.module, None) };
+    }
+}
+
+impl ShaderModule {
+    /// Creates a ShaderModule from SPIR-V words.
+    ///
+    /// Returns [`ShaderError::InvalidMagicNumber`] if the code is not SPIR-V.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # let event_loop = winit::event_loop::EventLoop::new();
+    /// # let window = winit::window::WindowBuilder::new().build(&event_loop)?;
+    /// # let device = plate::Device::new(&Default::default(), &Default::default(), Some(&window))?;
+    /// # let code = [0];
+    /// let shader = plate::ShaderModule::new(&device, &code)?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn new(device: &Arc<Device>, code: &[u32]) -> Result<Self, Error> {
+        validate_spirv(code)?;
+
+        let info = vk::ShaderModuleCreateInfo::builder().code(code);
+        let module = unsafe { device.create_shader_module(&info, None)? };
+
+        Ok(Self {
+            device: Arc::clone(device),
+            module,
+        })
+    }
+
+    /// Creates a ShaderModule from the bytes of a SPIR-V binary.
+    ///
+    /// Returns [`ShaderError::MisalignedCode`] if the length is not a multiple of 4 bytes, and
+    /// [`ShaderError::InvalidMagicNumber`] if the code is not SPIR-V. Binaries with the opposite
+    /// endianness are byte swapped.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # let event_loop = winit::event_loop::EventLoop::new();
+    /// # let window = winit::window::WindowBuilder::new().build(&event_loop)?;
+    /// # let device = plate::Device::new(&Default::default(), &Default::default(), Some(&window))?;
+    /// let bytes = std::fs::read("shader.vert.spv")?;
+    /// let shader = plate::ShaderModule::from_bytes(&device, &bytes)?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn from_bytes(device: &Arc<Device>, bytes: &[u8]) -> Result<Self, Error> {
+        Self::new(device, &spirv_words(bytes)?)
+    }
+
+    /// Creates a ShaderModule from a SPIR-V binary file.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # let event_loop = winit::event_loop::EventLoop::new();
+    /// # let window = winit::window::WindowBuilder::new().build(&event_loop)?;
+    /// # let device = plate::Device::new(&Default::default(), &Default::default(), Some(&window))?;
+    /// let shader = plate::ShaderModule::from_file(&device, "shaders/shader.vert.spv")?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn from_file<P: AsRef<Path>>(device: &Arc<Device>, path: P) -> Result<Self, Error> {
+        let bytes = std::fs::read(path).map_err(ShaderError::from)?;
+        Self::from_bytes(device, &bytes)
+    }
+}
+
+fn validate_spirv(code: &[u32]) -> Result<(), ShaderError> {
+    match code.first() {
+        None => Err(ShaderError::Empty),
+        Some(&SPIRV_MAGIC) => Ok(()),
+        Some(&magic) => Err(ShaderError::InvalidMagicNumber(magic)),
+    }
+}
+
+/// Converts the bytes of a SPIR-V binary to words in the host endianness.
+fn spirv_words(bytes: &[u8]) -> Result<Vec<u32>, ShaderError> {
+    let chunks = bytes.chunks_exact(4);
+    if !chunks.remainder().is_empty() {
+        return Err(ShaderError::MisalignedCode(bytes.len()))
+    }
+
+    let words = chunks
+        .map(|w| u32::from_ne_bytes([w[0], w[1], w[2], w[3]]))
+        .collect::<Vec<_>>();
+
+    match words.first() {
+        Some(&magic) if magic == SPIRV_MAGIC.swap_bytes() => Ok(words.into_iter().map(u32::swap_bytes).collect()),
+        _ => validate_spirv(&words).map(|_| words),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_spirv_words() {
+        let mut bytes = SPIRV_MAGIC.to_ne_bytes().to_vec();
+        bytes.extend_from_slice(&0x00010000u32.to_ne_bytes());
+        assert_eq!(spirv_words(&bytes).unwrap(), [SPIRV_MAGIC, 0x00010000]);
+
+        let swapped = bytes.chunks_exact(4).flat_map(|w| w.iter().rev().copied()).collect::<Vec<_>>();
+        assert_eq!(spirv_words(&swapped).unwrap(), [SPIRV_MAGIC, 0x00010000]);
+
+        assert!(matches!(spirv_words(&bytes[..6]), Err(ShaderError::MisalignedCode(6))));
+        assert!(matches!(spirv_words(&[]), Err(ShaderError::Empty)));
+        assert!(matches!(spirv_words(&[1, 2, 3, 4]), Err(ShaderError::InvalidMagicNumber(_))));
+    }
+}
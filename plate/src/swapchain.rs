@@ -4,6 +4,9 @@ use ash::{extensions::khr, vk};
 
 use crate::{Device, sync::*, image::*, Format, Error, Surface};
 
+pub use vk::SurfaceTransformFlagsKHR as SurfaceTransform;
+pub use vk::PresentModeKHR as PresentMode;
+
 /// Errors from the swapchain module.
 #[derive(thiserror::Error, Debug)]
 pub enum SwapchainError {
@@ -12,6 +15,58 @@ pub enum SwapchainError {
     NoSuitableDepthFormat,
 }
 
+/// Status of the [`Swapchain`] returned when acquiring and presenting images.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SwapchainStatus {
+    /// The swapchain matches the surface.
+    Optimal,
+    /// The swapchain can still be used but no longer matches the surface exactly, it should be
+    /// [`recreated`](Swapchain::recreate()) when convenient.
+    Suboptimal,
+    /// The swapchain no longer matches the surface and must be
+    /// [`recreated`](Swapchain::recreate()) before being used again.
+    OutOfDate,
+}
+
+/// Optional parameters for [`Swapchain`] creation.
+#[derive(Clone, Copy)]
+pub struct SwapchainParameters {
+    /// Whether to create a depth image matching the swapchain extent.
+    ///
+    /// The depth image is recreated alongside the swapchain images on
+    /// [`recreate()`](Swapchain::recreate()).
+    pub depth_image: bool,
+    /// Number of samples per pixel to render with.
+    ///
+    /// When greater than [`Sample1`](SampleCount::Sample1), a multisampled color image is created
+    /// alongside the swapchain images, to be resolved to them at the end of the
+    /// [`RenderPass`](crate::RenderPass). The depth image is created with the same sample count.
+    /// The count is clamped to the highest supported by the device, see
+    /// [`Swapchain::samples()`].
+    pub samples: SampleCount,
+    /// How images are queued for presentation.
+    ///
+    /// [`FIFO`](PresentMode::FIFO) waits for the vertical blank and is always supported,
+    /// [`MAILBOX`](PresentMode::MAILBOX) replaces the queued image to reduce latency and
+    /// [`IMMEDIATE`](PresentMode::IMMEDIATE) presents without waiting, which may tear. Falls back
+    /// to FIFO if the mode is not supported by the surface, see [`Swapchain::present_mode()`].
+    pub present_mode: PresentMode,
+    /// Minimum number of swapchain images, clamped to the surface limits. If `None`, one more
+    /// than the surface minimum is used. See [`Swapchain::image_count()`].
+    pub image_count: Option<u32>,
+}
+
+impl Default for SwapchainParameters {
+    fn default() -> Self {
+        Self {
+            depth_image: false,
+            samples: SampleCount::Sample1,
+            present_mode: PresentMode::FIFO,
+            image_count: None,
+        }
+    }
+}
+
 /// The Swapchain is responsible for providing images to be rendered to the screen.
 pub struct Swapchain {
     device: Arc<Device>,
@@ -21,9 +76,19 @@ pub struct Swapchain {
     swapchain_loader: khr::Swapchain,
     swapchain: vk::SwapchainKHR,
 
+    params: SwapchainParameters,
     extent: vk::Extent2D,
+    transform: SurfaceTransform,
+    present_mode: PresentMode,
+    zero_extent: bool,
 
     pub images: Vec<Image>,
+    /// Depth image with the same extent as the swapchain images, if requested in the
+    /// [`SwapchainParameters`].
+    pub depth_image: Option<Image>,
+    /// Multisampled color image with the same extent as the swapchain images, if the
+    /// [`samples()`](Self::samples()) are greater than [`Sample1`](SampleCount::Sample1).
+    pub msaa_image: Option<Image>,
     pub surface_format: Format,
     pub depth_format: Format,
 }
@@ -40,6 +105,10 @@ impl Drop for Swapchain {
 impl Swapchain {
     /// Creates a Swapchain.
     ///
+    /// If `depth_image` is set in the [`SwapchainParameters`], a depth image with the same extent
+    /// as the swapchain images is also created and can be used as a [`RenderPass`](crate::RenderPass)
+    /// depth attachment.
+    ///
     /// # Examples
     /// 
     /// ```no_run
@@ -47,31 +116,52 @@ impl Swapchain {
     /// # let event_loop = winit::event_loop::EventLoop::new();
     /// # let window = winit::window::WindowBuilder::new().build(&event_loop)?;
     /// # let device = plate::Device::new(&Default::default(), &Default::default(), Some(&window))?;
-    /// let swapchain = plate::Swapchain::new(&device, &window)?;
+    /// let swapchain = plate::Swapchain::new(&device, &window, &Default::default())?;
     /// # Ok::<(), Box<dyn std::error::Error>>(())
     /// ```
     pub fn new(
         device: &Arc<Device>,
         window: &winit::window::Window,
+        params: &SwapchainParameters,
     ) -> Result<Self, Error> {
-        let surface = Surface::new(&device.instance, &window)?;
+        let surface = Surface::new(&device.instance, window)?;
+
+        let limits = unsafe { device.instance.get_physical_device_properties(device.physical_device).limits };
+        let supported_samples = match params.depth_image {
+            true => limits.framebuffer_color_sample_counts & limits.framebuffer_depth_sample_counts,
+            false => limits.framebuffer_color_sample_counts,
+        };
+        let params = SwapchainParameters {
+            samples: params.samples.clamp(supported_samples),
+            ..*params
+        };
 
         let (
             swapchain_loader,
             swapchain,
             extent,
+            transform,
+            present_mode,
             images,
+            depth_image,
+            msaa_image,
             surface_format,
             depth_format,
-        ) = Self::create_swapchain(device, &surface, window, None)?;
+        ) = Self::create_swapchain(device, &surface, window, &params, None)?;
 
         Ok(Self {
-            device: Arc::clone(&device),
+            device: Arc::clone(device),
             surface,
             swapchain_loader,
             swapchain,
+            params,
             extent,
+            transform,
+            present_mode,
+            zero_extent: false,
             images,
+            depth_image,
+            msaa_image,
             surface_format,
             depth_format,
         })
@@ -79,7 +169,14 @@ impl Swapchain {
 
     /// Recreates the swapchain.
     ///
-    /// Sould be called if the window was resized or the surface format has changed.
+    /// Sould be called if the window was resized or the surface format has changed. The depth and
+    /// multisampled images are recreated with the new extent. The surface capabilities, formats
+    /// and present modes are queried again, and the device is waited on before the old swapchain
+    /// is destroyed.
+    ///
+    /// If the window has a zero extent, such as when it is minimized, the swapchain can not be
+    /// created. Recreation is skipped and [`next_image()`](Self::next_image()) returns `None`
+    /// until `recreate()` is called again with a non-zero window size. See [`is_zero_extent()`](Self::is_zero_extent()).
     ///
     /// # Examples
     /// 
@@ -88,21 +185,42 @@ impl Swapchain {
     /// # let event_loop = winit::event_loop::EventLoop::new();
     /// # let window = winit::window::WindowBuilder::new().build(&event_loop)?;
     /// # let device = plate::Device::new(&Default::default(), &Default::default(), Some(&window))?;
-    /// let mut swapchain = plate::Swapchain::new(&device, &window)?;
+    /// let mut swapchain = plate::Swapchain::new(&device, &window, &Default::default())?;
     /// swapchain.recreate(&window)?;
     /// # Ok::<(), Box<dyn std::error::Error>>(())
     /// ```
     pub fn recreate(&mut self, window: &winit::window::Window) -> Result<(), Error> {
         self.device.wait_idle()?;
-        
+
+        let surface_capabilities = unsafe {
+            self.surface
+                .surface_loader
+                .get_physical_device_surface_capabilities(
+                    self.device.physical_device,
+                    self.surface.surface,
+                )?
+        };
+        let window_extent = window.inner_size();
+        self.zero_extent = window_extent.width == 0
+            || window_extent.height == 0
+            || surface_capabilities.max_image_extent.width == 0
+            || surface_capabilities.max_image_extent.height == 0;
+        if self.zero_extent {
+            return Ok(())
+        }
+
         let (
             swapchain_loader,
             swapchain,
             extent,
+            transform,
+            present_mode,
             images,
+            depth_image,
+            msaa_image,
             surface_format,
             depth_format,
-        ) = Self::create_swapchain(&self.device, &self.surface, window, Some(self.swapchain))?;
+        ) = Self::create_swapchain(&self.device, &self.surface, window, &self.params, Some(self.swapchain))?;
 
         unsafe {
             self.swapchain_loader
@@ -112,7 +230,11 @@ impl Swapchain {
         self.swapchain_loader = swapchain_loader;
         self.swapchain = swapchain;
         self.extent = extent;
+        self.transform = transform;
+        self.present_mode = present_mode;
         self.images = images;
+        self.depth_image = depth_image;
+        self.msaa_image = msaa_image;
         self.surface_format = surface_format;
         self.depth_format = depth_format;
 
@@ -121,8 +243,12 @@ impl Swapchain {
 
     /// Acquires the next available swapchain image.
     ///
-    /// Returns the index of the next available image from the swapchain and whetherthe swapchain
-    /// is suboptimal. Will signal the provided semaphore when done.
+    /// Returns the index of the next available image from the swapchain and the
+    /// [`SwapchainStatus`], either [`Optimal`](SwapchainStatus::Optimal) or
+    /// [`Suboptimal`](SwapchainStatus::Suboptimal). Will signal the provided semaphore when done.
+    /// Returns `None` if the swapchain is out of date, in which case no image was acquired, the
+    /// semaphore will not be signaled and the swapchain must be [`recreated`](Self::recreate())
+    /// before trying again.
     ///
     /// # Examples
     /// 
@@ -130,25 +256,40 @@ impl Swapchain {
     /// # let event_loop = winit::event_loop::EventLoop::new();
     /// # let window = winit::window::WindowBuilder::new().build(&event_loop)?;
     /// # let device = plate::Device::new(&Default::default(), &Default::default(), Some(&window))?;
-    /// # let mut swapchain = plate::Swapchain::new(&device, &window)?;
+    /// # let mut swapchain = plate::Swapchain::new(&device, &window, &Default::default())?;
     /// # let acquire_sem = plate::Semaphore::new(&device, plate::SemaphoreFlags::empty())?;
-    /// let (image_index, _) = swapchain.next_image(&acquire_sem).unwrap();
+    /// let image_index = match swapchain.next_image(&acquire_sem)? {
+    ///     Some((image_index, _)) => image_index,
+    ///     None => return Ok(swapchain.recreate(&window)?),
+    /// };
     /// # Ok::<(), Box<dyn std::error::Error>>(())
     /// ```
-    pub fn next_image(&self, semaphore: &Semaphore) -> Result<(u32, bool), Error> {
-        Ok(unsafe {
+    pub fn next_image(&self, semaphore: &Semaphore) -> Result<Option<(u32, SwapchainStatus)>, Error> {
+        if self.zero_extent {
+            return Ok(None)
+        }
+
+        let result = unsafe {
             self.swapchain_loader.acquire_next_image(
                 self.swapchain,
                 u64::MAX,
                 **semaphore,
                 vk::Fence::null(),
-            )?
-        })
+            )
+        };
+
+        match result {
+            Ok((image_index, false)) => Ok(Some((image_index, SwapchainStatus::Optimal))),
+            Ok((image_index, true)) => Ok(Some((image_index, SwapchainStatus::Suboptimal))),
+            Err(vk::Result::ERROR_OUT_OF_DATE_KHR) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
     }
 
     /// Present the image at `image_index` to the screen.
     ///
-    /// Will wait on wait_semaphore.
+    /// Will wait on wait_semaphore. Returns the [`SwapchainStatus`], the swapchain should be
+    /// [`recreated`](Self::recreate()) if it is not [`Optimal`](SwapchainStatus::Optimal).
     ///
     /// # Examples
     ///
@@ -156,13 +297,15 @@ impl Swapchain {
     /// # let event_loop = winit::event_loop::EventLoop::new();
     /// # let window = winit::window::WindowBuilder::new().build(&event_loop)?;
     /// # let device = plate::Device::new(&Default::default(), &Default::default(), Some(&window))?;
-    /// # let mut swapchain = plate::Swapchain::new(&device, &window)?;
+    /// # let mut swapchain = plate::Swapchain::new(&device, &window, &Default::default())?;
     /// # let present_sem = plate::Semaphore::new(&device, plate::SemaphoreFlags::empty())?;
     /// let image_index = 0;
-    /// swapchain.present(image_index, &present_sem).unwrap();
+    /// if swapchain.present(image_index, &present_sem)? != plate::SwapchainStatus::Optimal {
+    ///     swapchain.recreate(&window)?;
+    /// }
     /// # Ok::<(), Box<dyn std::error::Error>>(())
     /// ```
-    pub fn present(&self, image_index: u32, wait_semaphore: &Semaphore) -> Result<bool, Error> {
+    pub fn present(&self, image_index: u32, wait_semaphore: &Semaphore) -> Result<SwapchainStatus, Error> {
         let swapchains = [self.swapchain];
         let wait_semaphores = [**wait_semaphore];
         let image_indices = [image_index];
@@ -172,11 +315,19 @@ impl Swapchain {
             .swapchains(&swapchains)
             .image_indices(&image_indices);
 
-        Ok(unsafe { self.swapchain_loader.queue_present(self.device.queue.queue, &present_info)? })
+        match unsafe { self.swapchain_loader.queue_present(self.device.queue.queue, &present_info) } {
+            Ok(false) => Ok(SwapchainStatus::Optimal),
+            Ok(true) => Ok(SwapchainStatus::Suboptimal),
+            Err(vk::Result::ERROR_OUT_OF_DATE_KHR) => Ok(SwapchainStatus::OutOfDate),
+            Err(e) => Err(e.into()),
+        }
     }
 
     /// Returns the aspect ration of the extent.
     ///
+    /// If the surface [`transform()`](Self::transform()) is rotated by 90 or 270 degrees, the
+    /// aspect ratio is of the presented image, after the rotation.
+    ///
     /// # Examples
     /// 
     /// ```no_run
@@ -184,12 +335,15 @@ impl Swapchain {
     /// # let event_loop = winit::event_loop::EventLoop::new();
     /// # let window = winit::window::WindowBuilder::new().build(&event_loop)?;
     /// # let device = plate::Device::new(&Default::default(), &Default::default(), Some(&window))?;
-    /// # let mut swapchain = plate::Swapchain::new(&device, &window)?;
+    /// # let mut swapchain = plate::Swapchain::new(&device, &window, &Default::default())?;
     /// let aspect_ratio = swapchain.aspect_ratio();
     /// # Ok::<(), Box<dyn std::error::Error>>(())
     /// ```
     pub fn aspect_ratio(&self) -> f32 {
-        (self.extent.width as f32) / (self.extent.height as f32)
+        match is_rotated(self.transform) {
+            true => (self.extent.height as f32) / (self.extent.width as f32),
+            false => (self.extent.width as f32) / (self.extent.height as f32),
+        }
     }
 
     /// Returns the swapchain extent.
@@ -200,7 +354,7 @@ impl Swapchain {
     /// # let event_loop = winit::event_loop::EventLoop::new();
     /// # let window = winit::window::WindowBuilder::new().build(&event_loop)?;
     /// # let device = plate::Device::new(&Default::default(), &Default::default(), Some(&window))?;
-    /// # let mut swapchain = plate::Swapchain::new(&device, &window)?;
+    /// # let mut swapchain = plate::Swapchain::new(&device, &window, &Default::default())?;
     /// let (width, height) = swapchain.extent();
     /// # Ok::<(), Box<dyn std::error::Error>>(())
     /// ```
@@ -208,16 +362,138 @@ impl Swapchain {
         (self.extent.width, self.extent.height)
     }
 
+    /// Returns the transform applied by the presentation engine to the swapchain images.
+    ///
+    /// The swapchain is created with the current surface transform to avoid the cost of the
+    /// presentation engine rotating the images. When the transform is rotated, such as on mobile
+    /// devices in a different orientation from the display, the rendered image must be rotated
+    /// accordingly, usually by pre-rotating the projection matrix.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # let event_loop = winit::event_loop::EventLoop::new();
+    /// # let window = winit::window::WindowBuilder::new().build(&event_loop)?;
+    /// # let device = plate::Device::new(&Default::default(), &Default::default(), Some(&window))?;
+    /// # let mut swapchain = plate::Swapchain::new(&device, &window, &Default::default())?;
+    /// let angle = match swapchain.transform() {
+    ///     plate::SurfaceTransform::ROTATE_90 => 90.0,
+    ///     plate::SurfaceTransform::ROTATE_180 => 180.0,
+    ///     plate::SurfaceTransform::ROTATE_270 => 270.0,
+    ///     _ => 0.0,
+    /// };
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn transform(&self) -> SurfaceTransform {
+        self.transform
+    }
+
+    /// Returns whether the last [`recreate()`](Self::recreate()) was skipped because the window
+    /// had a zero extent.
+    ///
+    /// Rendering should be paused while the window is minimized, calling `recreate()` again once
+    /// it is restored.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # let event_loop = winit::event_loop::EventLoop::new();
+    /// # let window = winit::window::WindowBuilder::new().build(&event_loop)?;
+    /// # let device = plate::Device::new(&Default::default(), &Default::default(), Some(&window))?;
+    /// # let mut swapchain = plate::Swapchain::new(&device, &window, &Default::default())?;
+    /// swapchain.recreate(&window)?;
+    /// if swapchain.is_zero_extent() {
+    ///     // skip rendering until the window is restored..
+    /// }
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn is_zero_extent(&self) -> bool {
+        self.zero_extent
+    }
+
+    /// Returns the number of samples per pixel of the [`msaa_image`](Self::msaa_image) and the
+    /// depth image.
+    ///
+    /// May be lower than requested in the [`SwapchainParameters`] if the device does not support
+    /// it. Pipelines rendering to the swapchain must be created with the same
+    /// `rasterization_samples`.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # let event_loop = winit::event_loop::EventLoop::new();
+    /// # let window = winit::window::WindowBuilder::new().build(&event_loop)?;
+    /// # let device = plate::Device::new(&Default::default(), &Default::default(), Some(&window))?;
+    /// let swapchain = plate::Swapchain::new(&device, &window, &plate::SwapchainParameters {
+    ///     samples: plate::SampleCount::Sample4,
+    ///     ..Default::default()
+    /// })?;
+    /// let samples = swapchain.samples();
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn samples(&self) -> SampleCount {
+        self.params.samples
+    }
+
+    /// Returns the present mode of the swapchain.
+    ///
+    /// Is [`FIFO`](PresentMode::FIFO) if the mode requested in the [`SwapchainParameters`] is not
+    /// supported by the surface.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # let event_loop = winit::event_loop::EventLoop::new();
+    /// # let window = winit::window::WindowBuilder::new().build(&event_loop)?;
+    /// # let device = plate::Device::new(&Default::default(), &Default::default(), Some(&window))?;
+    /// let swapchain = plate::Swapchain::new(&device, &window, &plate::SwapchainParameters {
+    ///     present_mode: plate::PresentMode::MAILBOX,
+    ///     ..Default::default()
+    /// })?;
+    /// let low_latency = swapchain.present_mode() == plate::PresentMode::MAILBOX;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn present_mode(&self) -> PresentMode {
+        self.present_mode
+    }
+
+    /// Returns the number of swapchain images.
+    ///
+    /// May differ from the `image_count` requested in the [`SwapchainParameters`], as it is
+    /// clamped to the surface limits and the presentation engine may create more images.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # let event_loop = winit::event_loop::EventLoop::new();
+    /// # let window = winit::window::WindowBuilder::new().build(&event_loop)?;
+    /// # let device = plate::Device::new(&Default::default(), &Default::default(), Some(&window))?;
+    /// let swapchain = plate::Swapchain::new(&device, &window, &plate::SwapchainParameters {
+    ///     image_count: Some(3),
+    ///     ..Default::default()
+    /// })?;
+    /// let image_count = swapchain.image_count();
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn image_count(&self) -> u32 {
+        self.images.len() as u32
+    }
+
     fn create_swapchain(
         device: &Arc<Device>,
         surface: &Surface,
         window: &winit::window::Window,
+        params: &SwapchainParameters,
         old_swapchain: Option<vk::SwapchainKHR>,
     ) -> Result<(
         khr::Swapchain,
         vk::SwapchainKHR,
         vk::Extent2D,
+        SurfaceTransform,
+        PresentMode,
         Vec<Image>,
+        Option<Image>,
+        Option<Image>,
         Format,
         Format,
     ), Error> {
@@ -254,18 +530,24 @@ impl Swapchain {
             })
             .unwrap_or(&surface_formats[0]);
 
-        let present_mode = *present_modes
-            .iter()
-            .find(|mode| **mode == vk::PresentModeKHR::FIFO)
-            .unwrap_or(&present_modes[0]);
+        let present_mode = match present_modes.contains(&params.present_mode) {
+            true => params.present_mode,
+            false => PresentMode::FIFO,
+        };
+
+        let transform = surface_capabilities.current_transform;
 
         let window_extent = window.inner_size();
+        let (width, height) = match is_rotated(transform) {
+            true => (window_extent.height, window_extent.width),
+            false => (window_extent.width, window_extent.height),
+        };
         let extent = vk::Extent2D {
-            width: window_extent.width.clamp(
+            width: width.clamp(
                 surface_capabilities.min_image_extent.width,
                 surface_capabilities.max_image_extent.width,
             ),
-            height: window_extent.height.clamp(
+            height: height.clamp(
                 surface_capabilities.min_image_extent.height,
                 surface_capabilities.max_image_extent.height,
             ),
@@ -273,10 +555,12 @@ impl Swapchain {
 
         let queue_families = [device.queue.family];
 
-        let image_count = if surface_capabilities.max_image_count == 0 {
-            surface_capabilities.min_image_count + 1
-        } else {
-            (surface_capabilities.min_image_count + 1).min(surface_capabilities.max_image_count)
+        let image_count = params.image_count
+            .unwrap_or(surface_capabilities.min_image_count + 1)
+            .max(surface_capabilities.min_image_count);
+        let image_count = match surface_capabilities.max_image_count {
+            0 => image_count,
+            max => image_count.min(max),
         };
 
         let mut swapchain_info = vk::SwapchainCreateInfoKHR::builder()
@@ -289,7 +573,7 @@ impl Swapchain {
             .image_usage(vk::ImageUsageFlags::COLOR_ATTACHMENT)
             .image_sharing_mode(vk::SharingMode::EXCLUSIVE)
             .queue_family_indices(&queue_families)
-            .pre_transform(surface_capabilities.current_transform)
+            .pre_transform(transform)
             .composite_alpha(vk::CompositeAlphaFlagsKHR::OPAQUE)
             .present_mode(present_mode)
             .clipped(true);
@@ -299,31 +583,63 @@ impl Swapchain {
             None => (),
         };
 
-        let swapchain_loader = khr::Swapchain::new(&device.instance, &device);
+        let swapchain_loader = khr::Swapchain::new(&device.instance, device);
         let swapchain = unsafe { swapchain_loader.create_swapchain(&swapchain_info, None)? };
 
         let images = unsafe { swapchain_loader.get_swapchain_images(swapchain)? }.into_iter()
             .map(|i| Image::from_vk_image(device, i, None, extent.width, extent.height, image_format.format, ImageAspectFlags::COLOR))
             .collect::<Result<Vec<_>, _>>()?;
 
-        let depth_format = [
-            vk::Format::D32_SFLOAT,
-            vk::Format::D32_SFLOAT_S8_UINT,
-            vk::Format::D24_UNORM_S8_UINT
-        ].into_iter()
-            .find(|format| {
-                let props = unsafe { device.instance.get_physical_device_format_properties(device.physical_device, *format) };
-                props.optimal_tiling_features.contains(vk::FormatFeatureFlags::DEPTH_STENCIL_ATTACHMENT)
-            })
+        let depth_format = depth_format(device)
             .ok_or(SwapchainError::NoSuitableDepthFormat)?;
 
+        let samples = params.samples;
+        let depth_image = match params.depth_image {
+            true => Some(Image::with_options(
+                device,
+                extent.width,
+                extent.height,
+                depth_format,
+                ImageUsageFlags::DEPTH_STENCIL_ATTACHMENT,
+                ImageAspectFlags::DEPTH,
+                &ImageOptions { samples, ..Default::default() },
+            )?),
+            false => None,
+        };
+
+        let msaa_image = match samples {
+            SampleCount::Sample1 => None,
+            _ => Some(Image::with_options(
+                device,
+                extent.width,
+                extent.height,
+                image_format.format,
+                ImageUsageFlags::COLOR_ATTACHMENT | ImageUsageFlags::TRANSIENT_ATTACHMENT,
+                ImageAspectFlags::COLOR,
+                &ImageOptions { samples, ..Default::default() },
+            )?),
+        };
+
         Ok((
             swapchain_loader,
             swapchain,
             extent,
+            transform,
+            present_mode,
             images,
+            depth_image,
+            msaa_image,
             image_format.format,
             depth_format,
         ))
     }
 }
+
+fn is_rotated(transform: SurfaceTransform) -> bool {
+    transform.intersects(
+        SurfaceTransform::ROTATE_90
+            | SurfaceTransform::ROTATE_270
+            | SurfaceTransform::HORIZONTAL_MIRROR_ROTATE_90
+            | SurfaceTransform::HORIZONTAL_MIRROR_ROTATE_270
+    )
+}
@@ -11,6 +11,10 @@ pub use vk::SemaphoreCreateFlags as SemaphoreFlags;
 ///
 /// Some GPU operations can set the Fence to be signaled or unsignaled, the host can then wait on
 /// for these operation to finish accordingly.
+///
+/// A Fence stays signaled until it is [`reset()`](Self::reset()). Submitting work with a Fence
+/// that is still signaled is invalid and will usually hang the application, use
+/// [`wait_and_reset()`](Self::wait_and_reset()) to reuse the same Fence across frames.
 pub struct Fence {
     device: Arc<Device>,
     fence: vk::Fence,
@@ -73,12 +77,31 @@ impl Fence {
     /// # let window = winit::window::WindowBuilder::new().build(&event_loop)?;
     /// # let device = plate::Device::new(&Default::default(), &Default::default(), Some(&window))?;
     /// # let fence = plate::Fence::new(&device, plate::FenceFlags::SIGNALED)?;
-    /// fence.wait()?;
+    /// fence.reset()?;
     /// # Ok::<(), Box<dyn std::error::Error>>(())
     /// ```
     pub fn reset(&self) -> Result<(), Error> {
         Ok(unsafe { self.device.reset_fences(&[self.fence])? })
     }
+
+    /// Block until the Fence is signaled, then resets it to unsignaled.
+    ///
+    /// `timeout` is the maximum time to wait in nanoseconds, `None` waits indefinitely. If the
+    /// timeout expires, a [`vk::Result::TIMEOUT`] error is returned and the Fence is not reset.
+    ///
+    /// Eamples
+    /// ```no_run
+    /// # let event_loop = winit::event_loop::EventLoop::new();
+    /// # let window = winit::window::WindowBuilder::new().build(&event_loop)?;
+    /// # let device = plate::Device::new(&Default::default(), &Default::default(), Some(&window))?;
+    /// # let fence = plate::Fence::new(&device, plate::FenceFlags::SIGNALED)?;
+    /// fence.wait_and_reset(None)?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn wait_and_reset(&self, timeout: Option<u64>) -> Result<(), Error> {
+        unsafe { self.device.wait_for_fences(&[self.fence], true, timeout.unwrap_or(u64::MAX))? };
+        self.reset()
+    }
 }
 
 /// Used to synchronize the execution of GPU instructions.
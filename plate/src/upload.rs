@@ -0,0 +1,423 @@
+use std::sync::Arc;
+
+use ash::vk;
+
+use crate::{image, AccessFlags, Buffer, BufferMemoryBarrier, BufferUsageFlags, CommandBuffer, CommandBufferLevel, CommandBufferUsageFlags, CommandPool, Device, Error, Fence, FenceFlags, ImageLayout, ImageMemoryBarrier, IndexBuffer, MemoryPropertyFlags, PipelineStage, Semaphore, SemaphoreFlags, SharingMode, Texture, TextureParameters, VertexBuffer};
+
+/// Records uploads of data to device local buffers and textures, and submits them to the
+/// transfer queue without blocking.
+///
+/// Unlike [`VertexBuffer::new()`], [`Texture::new()`] and the other staged constructors, which
+/// block until each copy is done, the uploads are batched and
+/// [`submit()`](Self::submit()) returns a [`PendingUpload`] to wait on, or to chain to a graphics
+/// submission. The resources must not be used by the GPU before the upload is complete.
+///
+/// If the Device has a dedicated transfer queue, see [`Device::has_transfer_queue()`], the
+/// copies run on it and the ownership of the resources is transferred to the graphics queue
+/// family. Work that needs a graphics queue, such as mipmap generation, runs on the graphics
+/// queue after the copies.
+pub struct UploadContext {
+    device: Arc<Device>,
+    transfer_pool: CommandPool,
+    graphics_pool: CommandPool,
+    transfer_cmd: CommandBuffer,
+    graphics_cmd: CommandBuffer,
+    staging: Vec<Buffer<u8>>,
+}
+
+impl UploadContext {
+    /// Creates a UploadContext.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # let event_loop = winit::event_loop::EventLoop::new();
+    /// # let window = winit::window::WindowBuilder::new().build(&event_loop)?;
+    /// # let device = plate::Device::new(&Default::default(), &Default::default(), Some(&window))?;
+    /// let mut upload = plate::UploadContext::new(&device)?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn new(device: &Arc<Device>) -> Result<Self, Error> {
+        let transfer_pool = CommandPool::new_transfer(device)?;
+        let graphics_pool = CommandPool::new(device)?;
+        let transfer_cmd = transfer_pool.alloc_cmd_buffer(CommandBufferLevel::PRIMARY)?;
+        let graphics_cmd = graphics_pool.alloc_cmd_buffer(CommandBufferLevel::PRIMARY)?;
+
+        transfer_cmd.begin(CommandBufferUsageFlags::ONE_TIME_SUBMIT)?;
+        graphics_cmd.begin(CommandBufferUsageFlags::ONE_TIME_SUBMIT)?;
+
+        Ok(Self {
+            device: Arc::clone(device),
+            transfer_pool,
+            graphics_pool,
+            transfer_cmd,
+            graphics_cmd,
+            staging: vec![],
+        })
+    }
+
+    /// Records the upload of a [`DEVICE_LOCAL`](MemoryPropertyFlags::DEVICE_LOCAL) Buffer\<T\>
+    /// filled with data from a slice.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # let event_loop = winit::event_loop::EventLoop::new();
+    /// # let window = winit::window::WindowBuilder::new().build(&event_loop)?;
+    /// # let device = plate::Device::new(&Default::default(), &Default::default(), Some(&window))?;
+    /// # let mut upload = plate::UploadContext::new(&device)?;
+    /// let data = [1u32, 2, 3, 4];
+    /// let buffer = upload.buffer(&data, plate::BufferUsageFlags::STORAGE_BUFFER)?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn buffer<T>(&mut self, data: &[T], usage: BufferUsageFlags) -> Result<Buffer<T>, Error> {
+        let buffer = Buffer::new(
+            &self.device,
+            data.len(),
+            usage | BufferUsageFlags::TRANSFER_DST,
+            SharingMode::EXCLUSIVE,
+            MemoryPropertyFlags::DEVICE_LOCAL,
+        )?;
+
+        let staging = buffer.staging(data)?;
+        buffer.record_copy_from(&self.transfer_cmd, &staging);
+        self.staging.push(staging);
+
+        let (dst_access, dst_stage) = buffer_access(usage);
+        let barrier = BufferMemoryBarrier::new(&buffer)
+            .src_access(AccessFlags::TRANSFER_WRITE)
+            .dst_access(dst_access);
+        self.release_and_acquire(dst_stage, &[barrier], &[]);
+
+        Ok(buffer)
+    }
+
+    /// Records the upload of a [`VertexBuffer`] with data from a slice.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// struct Vertex(f32);
+    /// # let event_loop = winit::event_loop::EventLoop::new();
+    /// # let window = winit::window::WindowBuilder::new().build(&event_loop)?;
+    /// # let device = plate::Device::new(&Default::default(), &Default::default(), Some(&window))?;
+    /// # let mut upload = plate::UploadContext::new(&device)?;
+    /// let vertices = [Vertex(0.0), Vertex(1.0)];
+    /// let vertex_buffer = upload.vertex_buffer(&vertices)?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn vertex_buffer<T>(&mut self, data: &[T]) -> Result<VertexBuffer<T>, Error> {
+        Ok(VertexBuffer(self.buffer(data, BufferUsageFlags::VERTEX_BUFFER)?))
+    }
+
+    /// Records the upload of a [`IndexBuffer`] with data from a slice.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # let event_loop = winit::event_loop::EventLoop::new();
+    /// # let window = winit::window::WindowBuilder::new().build(&event_loop)?;
+    /// # let device = plate::Device::new(&Default::default(), &Default::default(), Some(&window))?;
+    /// # let mut upload = plate::UploadContext::new(&device)?;
+    /// let indices = [0, 1, 2];
+    /// let index_buffer = upload.index_buffer(&indices)?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn index_buffer(&mut self, data: &[u32]) -> Result<IndexBuffer, Error> {
+        Ok(IndexBuffer(self.buffer(data, BufferUsageFlags::INDEX_BUFFER)?))
+    }
+
+    /// Records the upload of a [`Texture`] with data from a slice, as described by the
+    /// [`TextureParameters`].
+    ///
+    /// The texture is left in the [`SHADER_READ_ONLY_OPTIMAL`](ImageLayout::SHADER_READ_ONLY_OPTIMAL)
+    /// layout, `initial_layout` is ignored.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # let event_loop = winit::event_loop::EventLoop::new();
+    /// # let window = winit::window::WindowBuilder::new().build(&event_loop)?;
+    /// # let device = plate::Device::new(&Default::default(), &Default::default(), Some(&window))?;
+    /// # let mut upload = plate::UploadContext::new(&device)?;
+    /// # let (width, height) = (0, 0);
+    /// # let data = [0];
+    /// let texture = upload.texture(width, height, &data, &plate::TextureParameters {
+    ///     mipmaps: true,
+    ///     ..Default::default()
+    /// })?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn texture(&mut self, width: u32, height: u32, data: &[u8], params: &TextureParameters) -> Result<Texture, Error> {
+        self.texture_layers(width, height, &[data], vk::ImageCreateFlags::empty(), params)
+    }
+
+    /// Records the upload of a cubemap [`Texture`], with the faces in the same order as
+    /// [`Texture::new_cubemap()`].
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # let event_loop = winit::event_loop::EventLoop::new();
+    /// # let window = winit::window::WindowBuilder::new().build(&event_loop)?;
+    /// # let device = plate::Device::new(&Default::default(), &Default::default(), Some(&window))?;
+    /// # let mut upload = plate::UploadContext::new(&device)?;
+    /// # let size = 0;
+    /// # let (right, left, top, bottom, front, back) = ([0], [0], [0], [0], [0], [0]);
+    /// let skybox = upload.cubemap(size, &[&right, &left, &top, &bottom, &front, &back], &Default::default())?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn cubemap(&mut self, size: u32, faces: &[&[u8]; 6], params: &TextureParameters) -> Result<Texture, Error> {
+        self.texture_layers(size, size, faces, vk::ImageCreateFlags::CUBE_COMPATIBLE, params)
+    }
+
+    fn texture_layers(&mut self, width: u32, height: u32, layers: &[&[u8]], flags: vk::ImageCreateFlags, params: &TextureParameters) -> Result<Texture, Error> {
+        let texture = Texture::for_upload(&self.device, width, height, layers.len() as u32, flags, params)?;
+        let staging = image::layer_staging(&self.device, width, height, params.format, layers)?;
+        let (mip_levels, array_layers) = (texture.mip_levels, texture.array_layers);
+
+        self.transfer_cmd.pipeline_barrier(
+            PipelineStage::TOP_OF_PIPE,
+            PipelineStage::TRANSFER,
+            &[],
+            &[ImageMemoryBarrier::new(&texture)
+                .dst_access(AccessFlags::TRANSFER_WRITE)
+                .layouts(ImageLayout::UNDEFINED, ImageLayout::TRANSFER_DST_OPTIMAL)],
+        );
+        staging.record_copy_to_image(&self.transfer_cmd, texture.image, width, height, array_layers);
+        self.staging.push(staging);
+
+        let barrier = ImageMemoryBarrier::new(&texture).src_access(AccessFlags::TRANSFER_WRITE);
+        match mip_levels {
+            1 => self.release_and_acquire(PipelineStage::FRAGMENT_SHADER, &[], &[barrier
+                .dst_access(AccessFlags::SHADER_READ)
+                .layouts(ImageLayout::TRANSFER_DST_OPTIMAL, ImageLayout::SHADER_READ_ONLY_OPTIMAL)]),
+            _ => {
+                // Blits need a graphics queue, the mip levels are generated after the acquire
+                self.release_and_acquire(PipelineStage::TRANSFER, &[], &[barrier
+                    .dst_access(AccessFlags::TRANSFER_READ | AccessFlags::TRANSFER_WRITE)
+                    .layouts(ImageLayout::TRANSFER_DST_OPTIMAL, ImageLayout::TRANSFER_DST_OPTIMAL)]);
+                image::record_mipmaps(&self.device, &self.graphics_cmd, texture.image, width, height, mip_levels, array_layers);
+            }
+        }
+
+        Ok(texture)
+    }
+
+    /// Makes the transfer writes of the barriers visible to the `dst_stage` stages of the
+    /// graphics queue, transferring the ownership of the resources if the queue families differ.
+    fn release_and_acquire(&self, dst_stage: PipelineStage, buffer_barriers: &[BufferMemoryBarrier], image_barriers: &[ImageMemoryBarrier]) {
+        if !self.device.has_transfer_queue() {
+            // Same queue, the graphics command buffer is submitted after the transfer one
+            self.graphics_cmd.pipeline_barrier(PipelineStage::TRANSFER, dst_stage, buffer_barriers, image_barriers);
+            return
+        }
+
+        let (src, dst) = (self.device.transfer_queue_family(), self.device.queue_family());
+        let buffer_barriers = buffer_barriers.iter()
+            .map(|barrier| barrier.queue_families(src, dst))
+            .collect::<Vec<_>>();
+        let image_barriers = image_barriers.iter()
+            .map(|barrier| barrier.queue_families(src, dst))
+            .collect::<Vec<_>>();
+
+        self.transfer_cmd.pipeline_barrier(PipelineStage::TRANSFER, PipelineStage::BOTTOM_OF_PIPE, &buffer_barriers, &image_barriers);
+        self.graphics_cmd.pipeline_barrier(PipelineStage::TOP_OF_PIPE, dst_stage, &buffer_barriers, &image_barriers);
+    }
+
+    /// Submits the recorded uploads and returns a [`PendingUpload`] signaled once they are
+    /// complete.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # let event_loop = winit::event_loop::EventLoop::new();
+    /// # let window = winit::window::WindowBuilder::new().build(&event_loop)?;
+    /// # let device = plate::Device::new(&Default::default(), &Default::default(), Some(&window))?;
+    /// let mut upload = plate::UploadContext::new(&device)?;
+    /// let index_buffer = upload.index_buffer(&[0, 1, 2])?;
+    /// let pending = upload.submit()?;
+    /// // record other work..
+    /// pending.wait()?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn submit(self) -> Result<PendingUpload, Error> {
+        self.transfer_cmd.end()?;
+        self.graphics_cmd.end()?;
+
+        let fence = Fence::new(&self.device, FenceFlags::empty())?;
+        let semaphore = Semaphore::new(&self.device, SemaphoreFlags::empty())?;
+
+        let transferred = match self.device.has_transfer_queue() {
+            true => {
+                let transferred = Semaphore::new(&self.device, SemaphoreFlags::empty())?;
+                let transfer_fence = Fence::new(&self.device, FenceFlags::empty())?;
+                self.device.queue_submit_transfer(&[&self.transfer_cmd], &[], &[&transferred], Some(&transfer_fence))?;
+                let result = self.device.queue_submit_batch(
+                    &[&self.graphics_cmd],
+                    &[(&transferred, PipelineStage::ALL_COMMANDS)],
+                    &[&semaphore],
+                    Some(&fence),
+                );
+                if let Err(e) = result {
+                    // The transfer may still be reading the staging buffers
+                    transfer_fence.wait()?;
+                    return Err(e)
+                }
+                Some(transferred)
+            }
+            false => {
+                self.device.queue_submit_batch(&[&self.transfer_cmd, &self.graphics_cmd], &[], &[&semaphore], Some(&fence))?;
+                None
+            }
+        };
+
+        Ok(PendingUpload {
+            device: self.device,
+            fence,
+            semaphore,
+            transferred,
+            staging: self.staging,
+            transfer_pool: self.transfer_pool,
+            graphics_pool: self.graphics_pool,
+        })
+    }
+}
+
+/// Uploads submitted with [`UploadContext::submit()`], still executing on the GPU.
+///
+/// Owns the staging buffers until the uploads are complete, dropping a PendingUpload blocks until
+/// then.
+pub struct PendingUpload {
+    device: Arc<Device>,
+    fence: Fence,
+    semaphore: Semaphore,
+    #[allow(dead_code)]
+    transferred: Option<Semaphore>,
+    #[allow(dead_code)]
+    staging: Vec<Buffer<u8>>,
+    #[allow(dead_code)]
+    transfer_pool: CommandPool,
+    #[allow(dead_code)]
+    graphics_pool: CommandPool,
+}
+
+impl Drop for PendingUpload {
+    fn drop(&mut self) {
+        let _ = self.fence.wait();
+    }
+}
+
+impl PendingUpload {
+    /// Returns the Fence signaled once the uploads are complete.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # let event_loop = winit::event_loop::EventLoop::new();
+    /// # let window = winit::window::WindowBuilder::new().build(&event_loop)?;
+    /// # let device = plate::Device::new(&Default::default(), &Default::default(), Some(&window))?;
+    /// # let pending = plate::UploadContext::new(&device)?.submit()?;
+    /// pending.fence().wait()?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn fence(&self) -> &Fence {
+        &self.fence
+    }
+
+    /// Returns the Semaphore signaled once the uploads are complete.
+    ///
+    /// A graphics submission can wait on it to use the uploaded resources without blocking the
+    /// host. It must only be waited on once.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # let event_loop = winit::event_loop::EventLoop::new();
+    /// # let window = winit::window::WindowBuilder::new().build(&event_loop)?;
+    /// # let device = plate::Device::new(&Default::default(), &Default::default(), Some(&window))?;
+    /// # let cmd_pool = plate::CommandPool::new(&device)?;
+    /// # let cmd_buffer = cmd_pool.alloc_cmd_buffer(plate::CommandBufferLevel::PRIMARY)?;
+    /// # let pending = plate::UploadContext::new(&device)?.submit()?;
+    /// device.queue_submit_batch(
+    ///     &[&cmd_buffer],
+    ///     &[(pending.semaphore(), plate::PipelineStage::VERTEX_INPUT)],
+    ///     &[],
+    ///     None,
+    /// )?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn semaphore(&self) -> &Semaphore {
+        &self.semaphore
+    }
+
+    /// Returns whether the uploads are complete, without blocking.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # let event_loop = winit::event_loop::EventLoop::new();
+    /// # let window = winit::window::WindowBuilder::new().build(&event_loop)?;
+    /// # let device = plate::Device::new(&Default::default(), &Default::default(), Some(&window))?;
+    /// # let pending = plate::UploadContext::new(&device)?.submit()?;
+    /// if pending.is_complete()? {
+    ///     // use the resources..
+    /// }
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn is_complete(&self) -> Result<bool, Error> {
+        Ok(unsafe { self.device.get_fence_status(*self.fence)? })
+    }
+
+    /// Blocks until the uploads are complete and frees the staging buffers.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # let event_loop = winit::event_loop::EventLoop::new();
+    /// # let window = winit::window::WindowBuilder::new().build(&event_loop)?;
+    /// # let device = plate::Device::new(&Default::default(), &Default::default(), Some(&window))?;
+    /// # let pending = plate::UploadContext::new(&device)?.submit()?;
+    /// pending.wait()?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn wait(self) -> Result<(), Error> {
+        self.fence.wait()
+    }
+}
+
+/// Returns the accesses and pipeline stages a buffer with `usage` is typically read with.
+fn buffer_access(usage: BufferUsageFlags) -> (AccessFlags, PipelineStage) {
+    let shader_stages = PipelineStage::VERTEX_SHADER | PipelineStage::FRAGMENT_SHADER | PipelineStage::COMPUTE_SHADER;
+    let (access, stage) = [
+        (BufferUsageFlags::VERTEX_BUFFER, AccessFlags::VERTEX_ATTRIBUTE_READ, PipelineStage::VERTEX_INPUT),
+        (BufferUsageFlags::INDEX_BUFFER, AccessFlags::INDEX_READ, PipelineStage::VERTEX_INPUT),
+        (BufferUsageFlags::UNIFORM_BUFFER, AccessFlags::UNIFORM_READ, shader_stages),
+        (BufferUsageFlags::STORAGE_BUFFER, AccessFlags::SHADER_READ | AccessFlags::SHADER_WRITE, shader_stages),
+        (BufferUsageFlags::INDIRECT_BUFFER, AccessFlags::INDIRECT_COMMAND_READ, PipelineStage::DRAW_INDIRECT),
+    ].into_iter()
+        .filter(|(flag, _, _)| usage.contains(*flag))
+        .fold((AccessFlags::empty(), PipelineStage::empty()), |(access, stage), (_, a, s)| (access | a, stage | s));
+
+    match stage.is_empty() {
+        true => (AccessFlags::MEMORY_READ, PipelineStage::ALL_COMMANDS),
+        false => (access, stage),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_buffer_access() {
+        assert_eq!(
+            buffer_access(BufferUsageFlags::VERTEX_BUFFER | BufferUsageFlags::INDEX_BUFFER),
+            (AccessFlags::VERTEX_ATTRIBUTE_READ | AccessFlags::INDEX_READ, PipelineStage::VERTEX_INPUT),
+        );
+        assert_eq!(
+            buffer_access(BufferUsageFlags::TRANSFER_SRC),
+            (AccessFlags::MEMORY_READ, PipelineStage::ALL_COMMANDS),
+        );
+    }
+}